@@ -40,17 +40,17 @@ fn run_queries<R: Rank>(idx: &R, queries: &[usize]) {
 fn perform_bitvec_rank(group: &mut BenchmarkGroup<WallTime>, bits: &[bool], queries: &[usize]) {
     group.bench_function("sucds/BitVector", |b| {
         let idx = sucds::bit_vectors::BitVector::from_bits(bits.iter().cloned());
-        b.iter(|| run_queries(&idx, &queries));
+        b.iter(|| run_queries(&idx, queries));
     });
 
     group.bench_function("sucds/Rank9Sel", |b| {
         let idx = sucds::bit_vectors::Rank9Sel::from_bits(bits.iter().cloned());
-        b.iter(|| run_queries(&idx, &queries));
+        b.iter(|| run_queries(&idx, queries));
     });
 
     group.bench_function("sucds/SArray", |b| {
         let idx = sucds::bit_vectors::SArray::from_bits(bits.iter().cloned()).enable_rank();
-        b.iter(|| run_queries(&idx, &queries));
+        b.iter(|| run_queries(&idx, queries));
     });
 }
 
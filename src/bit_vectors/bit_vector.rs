@@ -256,7 +256,7 @@ impl BitVector {
             if len < WORD_LEN {
                 (1 << len) - 1
             } else {
-                std::usize::MAX
+                usize::MAX
             }
         };
         let bits = if shift + len <= WORD_LEN {
@@ -320,7 +320,7 @@ impl BitVector {
             if len < WORD_LEN {
                 (1 << len) - 1
             } else {
-                std::usize::MAX
+                usize::MAX
             }
         };
         let bits = bits & mask;
@@ -385,7 +385,7 @@ impl BitVector {
             if len < WORD_LEN {
                 (1 << len) - 1
             } else {
-                std::usize::MAX
+                usize::MAX
             }
         };
         let bits = bits & mask;
@@ -404,6 +404,145 @@ impl BitVector {
         Ok(())
     }
 
+    /// Copies `nbits` bits starting at the `src_offset`-th bit of `src`
+    /// to the bits starting at the `dst_offset`-th bit of `self`.
+    ///
+    /// The bits are copied in word units, where arbitrary offsets are handled by shifting
+    /// across word boundaries.
+    ///
+    /// # Arguments
+    ///
+    ///  - `dst_offset`: Bit position in `self` to be written.
+    ///  - `src`: Bit vector to be read.
+    ///  - `src_offset`: Bit position in `src` to be read.
+    ///  - `nbits`: Number of bits copied.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    ///  - `src.len() < src_offset + nbits`, or
+    ///  - `self.len() < dst_offset + nbits`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::bit_vectors::BitVector;
+    ///
+    /// let src = BitVector::from_bits([true, true, false, true]);
+    /// let mut dst = BitVector::from_bit(false, 5);
+    /// dst.copy_bits_from(2, &src, 1, 3)?;
+    /// assert_eq!(dst.get_bits(0, 5), Some(0b10100));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn copy_bits_from(
+        &mut self,
+        dst_offset: usize,
+        src: &Self,
+        src_offset: usize,
+        nbits: usize,
+    ) -> Result<()> {
+        if src_offset
+            .checked_add(nbits)
+            .map_or(true, |end| src.len() < end)
+        {
+            return Err(anyhow!(
+                "src_offset+nbits must be no greater than src.len()={}, but got {src_offset}+{nbits}.",
+                src.len()
+            ));
+        }
+        if dst_offset
+            .checked_add(nbits)
+            .map_or(true, |end| self.len() < end)
+        {
+            return Err(anyhow!(
+                "dst_offset+nbits must be no greater than self.len()={}, but got {dst_offset}+{nbits}.",
+                self.len()
+            ));
+        }
+        let mut copied = 0;
+        while copied < nbits {
+            let len = WORD_LEN.min(nbits - copied);
+            // NOTE: The ranges are checked above.
+            let bits = src.get_bits(src_offset + copied, len).unwrap();
+            self.set_bits(dst_offset + copied, bits, len).unwrap();
+            copied += len;
+        }
+        Ok(())
+    }
+
+    /// Appends the bits of `bv` at the end.
+    ///
+    /// The bits are copied in word units with [`Self::copy_bits_from()`].
+    ///
+    /// # Arguments
+    ///
+    ///  - `bv`: Bit vector to be appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sucds::bit_vectors::BitVector;
+    ///
+    /// let mut bv = BitVector::from_bits([true, false]);
+    /// bv.append_bits(&BitVector::from_bits([false, true, true]));
+    /// assert_eq!(bv.len(), 5);
+    /// assert_eq!(bv.get_bits(0, 5), Some(0b11001));
+    /// ```
+    pub fn append_bits(&mut self, bv: &Self) {
+        let pos = self.len;
+        self.len += bv.len();
+        self.words.resize(Self::words_for(self.len), 0);
+        // NOTE: Copying should be safe since the length is extended.
+        self.copy_bits_from(pos, bv, 0, bv.len()).unwrap();
+    }
+
+    /// Returns the positions of bits that are set in `self` but not in `base` (added) and
+    /// those that are set in `base` but not in `self` (removed), in increasing order.
+    ///
+    /// If the lengths differ, bits beyond the shorter one are treated as unset in it.
+    ///
+    /// # Arguments
+    ///
+    /// - `base`: Bit vector to be compared.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n / w + d)`$ for $`d`$ differing bits, where $`w`$ is [`WORD_LEN`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sucds::bit_vectors::BitVector;
+    ///
+    /// let base = BitVector::from_bits([true, true, false, false]);
+    /// let bv = BitVector::from_bits([true, false, true, false, false, true]);
+    ///
+    /// let (added, removed) = bv.diff(&base);
+    /// assert_eq!(added, vec![2, 5]);
+    /// assert_eq!(removed, vec![1]);
+    /// ```
+    pub fn diff(&self, base: &Self) -> (Vec<usize>, Vec<usize>) {
+        let (mut added, mut removed) = (vec![], vec![]);
+        for i in 0..self.num_words().max(base.num_words()) {
+            let x = self.words.get(i).cloned().unwrap_or(0);
+            let y = base.words.get(i).cloned().unwrap_or(0);
+            let mut changed = x ^ y;
+            while changed != 0 {
+                let shift = changed.trailing_zeros() as usize;
+                if x >> shift & 1 == 1 {
+                    added.push(i * WORD_LEN + shift);
+                } else {
+                    removed.push(i * WORD_LEN + shift);
+                }
+                changed &= changed - 1;
+            }
+        }
+        (added, removed)
+    }
+
     /// Returns the largest bit position `pred` such that `pred <= pos` and the `pred`-th bit is set, or
     /// [`None`] if not found or `self.len() <= pos`.
     ///
@@ -580,7 +719,7 @@ impl BitVector {
     /// assert_eq!(it.next(), Some(false));
     /// assert_eq!(it.next(), None);
     /// ```
-    pub const fn iter(&self) -> Iter {
+    pub const fn iter(&self) -> Iter<'_> {
         Iter::new(self)
     }
 
@@ -601,7 +740,7 @@ impl BitVector {
     /// assert_eq!(it.next(), Some(3));
     /// assert_eq!(it.next(), None);
     /// ```
-    pub fn unary_iter(&self, pos: usize) -> UnaryIter {
+    pub fn unary_iter(&self, pos: usize) -> UnaryIter<'_> {
         UnaryIter::new(self, pos)
     }
 
@@ -954,6 +1093,9 @@ impl Serializable for BitVector {
 mod tests {
     use super::*;
 
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
     #[test]
     fn test_set_bit_oob() {
         let mut bv = BitVector::from_bit(false, 3);
@@ -1035,6 +1177,115 @@ mod tests {
         assert_eq!(bv.get_word64(60), Some(0b1111));
     }
 
+    #[test]
+    fn test_copy_bits_from() {
+        let mut rng = ChaChaRng::seed_from_u64(13);
+        let src = BitVector::from_bits((0..200).map(|_| rng.gen::<bool>()));
+        let dst = BitVector::from_bits((0..200).map(|_| rng.gen::<bool>()));
+        for src_offset in 0..70 {
+            for dst_offset in 0..70 {
+                for nbits in [0, 1, 2, 63, 64, 65, 127, 128, 129] {
+                    let mut bv = dst.clone();
+                    bv.copy_bits_from(dst_offset, &src, src_offset, nbits)
+                        .unwrap();
+                    for i in 0..bv.len() {
+                        let expected = if (dst_offset..dst_offset + nbits).contains(&i) {
+                            src.get_bit(i - dst_offset + src_offset)
+                        } else {
+                            dst.get_bit(i)
+                        };
+                        assert_eq!(bv.get_bit(i), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_append_bits() {
+        let mut rng = ChaChaRng::seed_from_u64(17);
+        let mut bv = BitVector::new();
+        let mut expected = vec![];
+        for len in [0, 1, 5, 63, 64, 65, 130, 0, 7] {
+            let bits: Vec<bool> = (0..len).map(|_| rng.gen::<bool>()).collect();
+            bv.append_bits(&BitVector::from_bits(bits.iter().cloned()));
+            expected.extend(bits);
+            assert_eq!(bv, BitVector::from_bits(expected.iter().cloned()));
+        }
+    }
+
+    #[test]
+    fn test_copy_bits_from_oob() {
+        let src = BitVector::from_bit(true, 10);
+        let mut dst = BitVector::from_bit(false, 10);
+        let e = dst.copy_bits_from(0, &src, 5, 6);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("src_offset+nbits must be no greater than src.len()=10, but got 5+6.".to_string())
+        );
+        let e = dst.copy_bits_from(8, &src, 0, 3);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(
+                "dst_offset+nbits must be no greater than self.len()=10, but got 8+3.".to_string()
+            )
+        );
+        let e = dst.copy_bits_from(0, &src, usize::MAX, 2);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "src_offset+nbits must be no greater than src.len()=10, but got {}+2.",
+                usize::MAX
+            ))
+        );
+        let e = dst.copy_bits_from(usize::MAX, &src, 0, 2);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "dst_offset+nbits must be no greater than self.len()=10, but got {}+2.",
+                usize::MAX
+            ))
+        );
+    }
+
+    #[test]
+    fn test_diff_known() {
+        let base = BitVector::from_bits((0..200).map(|i| i % 3 == 0));
+        let mut bv = base.clone();
+        bv.set_bit(0, false).unwrap();
+        bv.set_bit(64, true).unwrap();
+        bv.set_bit(99, false).unwrap();
+        bv.set_bit(130, true).unwrap();
+        let (added, removed) = bv.diff(&base);
+        assert_eq!(added, vec![64, 130]);
+        assert_eq!(removed, vec![0, 99]);
+
+        let (added, removed) = base.diff(&bv);
+        assert_eq!(added, vec![0, 99]);
+        assert_eq!(removed, vec![64, 130]);
+        assert_eq!(bv.diff(&bv), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_diff_random() {
+        let mut rng = ChaChaRng::seed_from_u64(149);
+        for &(la, lb) in &[(0, 0), (0, 70), (100, 100), (130, 64), (1000, 777)] {
+            let a: Vec<bool> = (0..la).map(|_| rng.gen_bool(0.5)).collect();
+            let b: Vec<bool> = (0..lb).map(|_| rng.gen_bool(0.5)).collect();
+            let bit = |v: &[bool], i: usize| v.get(i).cloned().unwrap_or(false);
+            let expected_added: Vec<_> = (0..la.max(lb))
+                .filter(|&i| bit(&a, i) && !bit(&b, i))
+                .collect();
+            let expected_removed: Vec<_> = (0..la.max(lb))
+                .filter(|&i| !bit(&a, i) && bit(&b, i))
+                .collect();
+            let (added, removed) = BitVector::from_bits(a.iter().cloned())
+                .diff(&BitVector::from_bits(b.iter().cloned()));
+            assert_eq!(added, expected_added);
+            assert_eq!(removed, expected_removed);
+        }
+    }
+
     #[test]
     fn test_serialize() {
         let mut bytes = vec![];
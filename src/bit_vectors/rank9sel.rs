@@ -92,6 +92,34 @@ impl Rank9Sel {
         Self::new(BitVector::from_bits(bits))
     }
 
+    /// Appends the bits of `bv` at the end, updating the index incrementally.
+    ///
+    /// Only the index for the last block of the original bits and the appended region is recomputed,
+    /// rather than rebuilding from scratch.
+    /// Queries over the original region remain valid, and the appended region becomes queryable.
+    ///
+    /// # Arguments
+    ///
+    /// - `bv`: Bits to be appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sucds::bit_vectors::{BitVector, Rank9Sel, Rank, Select};
+    ///
+    /// let mut bv = Rank9Sel::from_bits([true, false, false]).select1_hints();
+    /// bv.append_bits(&BitVector::from_bits([true, true]));
+    ///
+    /// assert_eq!(bv.len(), 5);
+    /// assert_eq!(bv.rank1(4), Some(2));
+    /// assert_eq!(bv.select1(2), Some(4));
+    /// ```
+    pub fn append_bits(&mut self, bv: &BitVector) {
+        self.bv.append_bits(bv);
+        // NOTE: self.bv is the one used in construction, with bits appended.
+        unsafe { self.rs.update_appended(&self.bv) };
+    }
+
     /// Returns the reference of the internal bit vector.
     pub const fn bit_vector(&self) -> &BitVector {
         &self.bv
@@ -300,6 +328,9 @@ impl Serializable for Rank9Sel {
 mod tests {
     use super::*;
 
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
     #[test]
     fn test_rank1_all_zeros() {
         let bv = Rank9Sel::from_bits([false, false, false]);
@@ -348,6 +379,44 @@ mod tests {
         assert_eq!(bv.select1(2), None);
     }
 
+    #[test]
+    fn test_append_bits() {
+        let mut rng = ChaChaRng::seed_from_u64(7);
+        let mut bits = vec![];
+        let mut bv = Rank9Sel::new(BitVector::new())
+            .select1_hints()
+            .select0_hints();
+        for &n in &[0, 1, 63, 64, 448, 1, 511, 512, 513, 3000, 7] {
+            let chunk: Vec<bool> = (0..n).map(|_| rng.gen_bool(0.5)).collect();
+            bv.append_bits(&BitVector::from_bits(chunk.iter().cloned()));
+            bits.extend(chunk);
+
+            let expected = Rank9Sel::from_bits(bits.iter().cloned())
+                .select1_hints()
+                .select0_hints();
+            assert_eq!(bv, expected);
+            for i in 0..=bits.len() {
+                assert_eq!(bv.rank1(i), expected.rank1(i));
+            }
+            for k in 0..=bv.num_ones() {
+                assert_eq!(bv.select1(k), expected.select1(k));
+            }
+            for k in 0..=bv.num_zeros() {
+                assert_eq!(bv.select0(k), expected.select0(k));
+            }
+        }
+    }
+
+    #[test]
+    fn test_append_bits_no_hint() {
+        let mut bv = Rank9Sel::from_bits([true, false]);
+        bv.append_bits(&BitVector::from_bit(true, 600));
+        assert_eq!(bv, Rank9Sel::new(bv.bit_vector().clone()));
+        assert_eq!(bv.rank1(602), Some(601));
+        assert_eq!(bv.select1(600), Some(601));
+        assert_eq!(bv.select0(0), Some(1));
+    }
+
     #[test]
     fn test_serialize() {
         let mut bytes = vec![];
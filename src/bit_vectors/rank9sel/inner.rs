@@ -40,14 +40,51 @@ impl Rank9SelIndex {
         self.build_select0()
     }
 
+    /// Updates the index for bits appended to the bit vector used in construction.
+    ///
+    /// Only the blocks affected by the appended bits are recomputed
+    /// (and the select hints are rebuilt over the block ranks if enabled).
+    ///
+    /// # Arguments
+    ///
+    /// - `bv`: Bit vector used in construction, with bits appended.
+    ///
+    /// # Safety
+    ///
+    /// `bv` must be the one used in construction with bits appended at the end.
+    pub unsafe fn update_appended(&mut self, bv: &BitVector) {
+        self.extend_rank(bv);
+        if self.select1_hints.is_some() {
+            *self = std::mem::take(self).build_select1();
+        }
+        if self.select0_hints.is_some() {
+            *self = std::mem::take(self).build_select0();
+        }
+    }
+
     fn build_rank(bv: &BitVector) -> Self {
-        let mut next_rank = 0;
+        let mut idx = Self::default();
+        idx.extend_rank(bv);
+        idx
+    }
+
+    fn extend_rank(&mut self, bv: &BitVector) {
+        // Full blocks consisting only of full words are unaffected by appended bits.
+        let done_blocks = self.len / 64 / BLOCK_LEN;
+        if done_blocks == 0 {
+            self.block_rank_pairs.clear();
+            self.block_rank_pairs.push(0);
+        } else {
+            self.block_rank_pairs.truncate(done_blocks * 2 + 1);
+        }
+
+        let mut next_rank = self.block_rank_pairs[done_blocks * 2];
         let mut cur_subrank = 0;
         let mut subranks = 0;
 
-        let mut block_rank_pairs = vec![next_rank];
+        let block_rank_pairs = &mut self.block_rank_pairs;
 
-        for i in 0..bv.num_words() {
+        for i in done_blocks * BLOCK_LEN..bv.num_words() {
             let word_pop = broadword::popcount(bv.words()[i]);
 
             let shift = i % BLOCK_LEN;
@@ -80,12 +117,7 @@ impl Rank9SelIndex {
         }
         block_rank_pairs.shrink_to_fit();
 
-        Self {
-            len: bv.num_bits(),
-            block_rank_pairs,
-            select1_hints: None,
-            select0_hints: None,
-        }
+        self.len = bv.num_bits();
     }
 
     fn build_select1(mut self) -> Self {
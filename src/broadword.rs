@@ -124,7 +124,7 @@ pub fn lsb(x: usize) -> Option<usize> {
         if x == 0 {
             None
         } else {
-            Some(bit_position(x & std::usize::MAX.wrapping_mul(x)))
+            Some(bit_position(x & usize::MAX.wrapping_mul(x)))
         }
     }
     #[cfg(feature = "intrinsics")]
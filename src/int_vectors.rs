@@ -35,6 +35,11 @@
 //! This is the only updatable data structure and will be the fastest due to its simplicity.
 //! However, the compression performance is poor, especially when $`A`$ contains at least one large value.
 //!
+//! [`CompactDeque`] is a double-ended queue built on the same fixed-width representation,
+//! supporting pushes and pops at both ends in amortized constant time.
+//! [`RmqIndex`] supports constant-time range minimum queries over [`CompactVector`],
+//! and [`Permutation`] supports inverse lookups of a permutation stored in [`CompactVector`].
+//!
 //! ## Compressed format with Elias-Fano encoding
 //!
 //! [`PrefixSummedEliasFano`] is a compressed data structure that stores the prefix-summed sequence from $`A`$
@@ -77,16 +82,22 @@
 //! # Ok(())
 //! # }
 //! ```
+pub mod compact_deque;
 pub mod compact_vector;
 pub mod dacs_byte;
 pub mod dacs_opt;
+pub mod permutation;
 pub mod prefix_summed_elias_fano;
 pub mod prelude;
+pub mod rmq_index;
 
+pub use compact_deque::CompactDeque;
 pub use compact_vector::CompactVector;
 pub use dacs_byte::DacsByte;
 pub use dacs_opt::DacsOpt;
+pub use permutation::Permutation;
 pub use prefix_summed_elias_fano::PrefixSummedEliasFano;
+pub use rmq_index::RmqIndex;
 
 use anyhow::Result;
 use num_traits::ToPrimitive;
@@ -0,0 +1,347 @@
+//! Double-ended queue in which each integer is represented in a fixed number of bits.
+#![cfg(target_pointer_width = "64")]
+
+use anyhow::Result;
+
+use crate::int_vectors::prelude::*;
+use crate::int_vectors::CompactVector;
+
+/// Double-ended queue in which each integer is represented in a fixed number of bits.
+///
+/// This is a ring buffer over the slots of [`CompactVector`].
+/// The `i`-th integer from the front is stored at the slot `(head + i) mod capacity`,
+/// meaning that element offsets wrap around in the bit space of the buffer.
+/// When the buffer is full, its capacity is doubled.
+///
+/// # Memory usage
+///
+/// $`m \lceil \lg u \rceil`$ bits for the capacity of $`m`$ integers in which a value is in $`[0,u)`$.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sucds::int_vectors::CompactDeque;
+///
+/// // Can store integers within 3 bits each.
+/// let mut dq = CompactDeque::new(3)?;
+///
+/// dq.push_back(7)?;
+/// dq.push_front(2)?;
+/// dq.push_back(5)?;
+///
+/// assert_eq!(dq.len(), 3);
+/// assert_eq!(dq.get_int(0), Some(2));
+///
+/// assert_eq!(dq.pop_front(), Some(2));
+/// assert_eq!(dq.pop_back(), Some(5));
+/// assert_eq!(dq.pop_back(), Some(7));
+/// assert_eq!(dq.pop_back(), None);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompactDeque {
+    buf: CompactVector,
+    head: usize,
+    len: usize,
+}
+
+impl CompactDeque {
+    /// Creates a new empty deque storing integers within `width` bits each.
+    ///
+    /// # Arguments
+    ///
+    ///  - `width`: Number of bits used to store an integer.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `width` is not in `1..=64`.
+    pub fn new(width: usize) -> Result<Self> {
+        Self::with_capacity(0, width)
+    }
+
+    /// Creates a new empty deque storing integers within `width` bits each,
+    /// where space for storing `capa` integers is reserved.
+    ///
+    /// # Arguments
+    ///
+    ///  - `capa`: Number of elements reserved.
+    ///  - `width`: Number of bits used to store an integer.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `width` is not in `1..=64`.
+    pub fn with_capacity(capa: usize, width: usize) -> Result<Self> {
+        Ok(Self {
+            buf: CompactVector::from_int(0, capa, width)?,
+            head: 0,
+            len: 0,
+        })
+    }
+
+    /// Pushes integer `val` at the end.
+    ///
+    /// # Arguments
+    ///
+    ///  - `val`: Integer value pushed.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `val` cannot be represent in `self.width()` bits.
+    ///
+    /// # Complexity
+    ///
+    /// Constant (Amortized)
+    pub fn push_back(&mut self, val: usize) -> Result<()> {
+        self.buf.check_fit(val)?;
+        self.reserve_one();
+        let slot = self.slot(self.len);
+        // NOTE: Setting should be safe.
+        self.buf.set_int(slot, val).unwrap();
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Pushes integer `val` at the front.
+    ///
+    /// # Arguments
+    ///
+    ///  - `val`: Integer value pushed.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `val` cannot be represent in `self.width()` bits.
+    ///
+    /// # Complexity
+    ///
+    /// Constant (Amortized)
+    pub fn push_front(&mut self, val: usize) -> Result<()> {
+        self.buf.check_fit(val)?;
+        self.reserve_one();
+        self.head = self.slot(self.capacity() - 1);
+        // NOTE: Setting should be safe.
+        self.buf.set_int(self.head, val).unwrap();
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the last integer and returns it, or [`None`] if the deque is empty.
+    ///
+    /// # Complexity
+    ///
+    /// Constant
+    pub fn pop_back(&mut self) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        self.len -= 1;
+        self.buf.get_int(self.slot(self.len))
+    }
+
+    /// Removes the first integer and returns it, or [`None`] if the deque is empty.
+    ///
+    /// # Complexity
+    ///
+    /// Constant
+    pub fn pop_front(&mut self) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let x = self.buf.get_int(self.head);
+        self.head = self.slot(1);
+        self.len -= 1;
+        x
+    }
+
+    /// Returns the `pos`-th integer from the front, or [`None`] if out of bounds.
+    ///
+    /// # Arguments
+    ///
+    ///  - `pos`: Position.
+    ///
+    /// # Complexity
+    ///
+    /// Constant
+    pub fn get_int(&self, pos: usize) -> Option<usize> {
+        if self.len <= pos {
+            return None;
+        }
+        self.buf.get_int(self.slot(pos))
+    }
+
+    /// Returns the first integer, or [`None`] if the deque is empty.
+    pub fn front(&self) -> Option<usize> {
+        self.get_int(0)
+    }
+
+    /// Returns the last integer, or [`None`] if the deque is empty.
+    pub fn back(&self) -> Option<usize> {
+        self.len.checked_sub(1).and_then(|pos| self.get_int(pos))
+    }
+
+    /// Creates an iterator for enumerating integers from the front.
+    pub const fn iter(&self) -> Iter<'_> {
+        Iter::new(self)
+    }
+
+    /// Gets the number of integers.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the deque is empty.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of integers it can hold without reallocating.
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Gets the number of bits to represent an integer.
+    #[inline(always)]
+    pub const fn width(&self) -> usize {
+        self.buf.width()
+    }
+
+    #[inline(always)]
+    const fn slot(&self, pos: usize) -> usize {
+        (self.head + pos) % self.capacity()
+    }
+
+    fn reserve_one(&mut self) {
+        if self.len < self.capacity() {
+            return;
+        }
+        let new_capa = (self.capacity() * 2).max(4);
+        // NOTE: Creating should be safe.
+        let mut buf = CompactVector::from_int(0, new_capa, self.width()).unwrap();
+        // The buffer is full, so the integers are in [head, capacity) followed by [0, head).
+        let tail_len = self.capacity() - self.head;
+        buf.copy_range_from(0, &self.buf, self.head..self.capacity());
+        buf.copy_range_from(tail_len, &self.buf, 0..self.head);
+        self.buf = buf;
+        self.head = 0;
+    }
+}
+
+impl NumVals for CompactDeque {
+    /// Returns the number of integers stored (just wrapping [`Self::len()`]).
+    fn num_vals(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Access for CompactDeque {
+    /// Returns the `pos`-th integer from the front, or [`None`] if out of bounds
+    /// (just wrapping [`Self::get_int()`]).
+    fn access(&self, pos: usize) -> Option<usize> {
+        self.get_int(pos)
+    }
+}
+
+/// Iterator for enumerating integers from the front, created by [`CompactDeque::iter()`].
+pub struct Iter<'a> {
+    dq: &'a CompactDeque,
+    pos: usize,
+}
+
+impl<'a> Iter<'a> {
+    /// Creates a new iterator.
+    pub const fn new(dq: &'a CompactDeque) -> Self {
+        Self { dq, pos: 0 }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos < self.dq.len() {
+            let x = self.dq.get_int(self.pos).unwrap();
+            self.pos += 1;
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = self.dq.len() - self.pos;
+        (rest, Some(rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::VecDeque;
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn test_random_ops() {
+        let mut rng = ChaChaRng::seed_from_u64(13);
+        let mut dq = CompactDeque::new(5).unwrap();
+        let mut expected = VecDeque::new();
+        for _ in 0..10000 {
+            match rng.gen_range(0..4) {
+                0 => {
+                    let x = rng.gen_range(0..32);
+                    dq.push_back(x).unwrap();
+                    expected.push_back(x);
+                }
+                1 => {
+                    let x = rng.gen_range(0..32);
+                    dq.push_front(x).unwrap();
+                    expected.push_front(x);
+                }
+                2 => assert_eq!(dq.pop_back(), expected.pop_back()),
+                _ => assert_eq!(dq.pop_front(), expected.pop_front()),
+            }
+            assert_eq!(dq.len(), expected.len());
+            assert_eq!(dq.front(), expected.front().cloned());
+            assert_eq!(dq.back(), expected.back().cloned());
+        }
+        assert!(dq.iter().eq(expected.iter().cloned()));
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        let mut dq = CompactDeque::with_capacity(4, 64).unwrap();
+        dq.push_front(u64::MAX as usize).unwrap();
+        dq.push_back(1).unwrap();
+        dq.push_front(2).unwrap();
+        dq.push_back(3).unwrap();
+        assert_eq!(dq.capacity(), 4);
+        assert_eq!(
+            dq.iter().collect::<Vec<_>>(),
+            vec![2, u64::MAX as usize, 1, 3]
+        );
+        dq.push_back(4).unwrap();
+        assert_eq!(dq.capacity(), 8);
+        assert_eq!(
+            dq.iter().collect::<Vec<_>>(),
+            vec![2, u64::MAX as usize, 1, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_push_unfit() {
+        let mut dq = CompactDeque::new(2).unwrap();
+        let e = dq.push_front(4);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("val must fit in self.width()=2 bits, but got 4.".to_string())
+        );
+    }
+}
@@ -1,15 +1,43 @@
 //! Updatable compact vector in which each integer is represented in a fixed number of bits.
 #![cfg(target_pointer_width = "64")]
 
-use std::io::{Read, Write};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::convert::TryFrom;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
 
 use anyhow::{anyhow, Result};
 use num_traits::ToPrimitive;
 
-use crate::bit_vectors::BitVector;
+use crate::bit_vectors::{BitVector, NumBits};
 use crate::int_vectors::prelude::*;
+use crate::int_vectors::RmqIndex;
+use crate::mii_sequences::{EliasFano, Mmphf};
 use crate::{utils, Serializable};
 
+/// Maximum width for which histograms are counted in a dense array of `2^width` counters.
+const DENSE_HISTOGRAM_MAX_WIDTH: usize = 16;
+
+/// Maximum width for which integers in a word are matched at once in [`CompactVector::replace_value()`].
+const BROADWORD_MATCH_MAX_WIDTH: usize = 16;
+
+/// Base of the polynomial hash in [`CompactVector::rolling_hashes()`].
+pub const ROLLING_HASH_BASE: u64 = 0x0123_4567_89ab_cdef;
+
+/// Modulus of the polynomial hash in [`CompactVector::rolling_hashes()`], i.e., $`2^{61} - 1`$.
+pub const ROLLING_HASH_MODULUS: u64 = (1 << 61) - 1;
+
+/// Magic number at the beginning of [`CompactVector::serialize_aligned_into()`].
+pub const ALIGNED_MAGIC: [u8; 8] = *b"SUCDSCV\0";
+
+/// Format version of [`CompactVector::serialize_aligned_into()`].
+pub const ALIGNED_VERSION: u32 = 1;
+
+/// Number of bytes of the header in [`CompactVector::serialize_aligned_into()`],
+/// i.e., the offset of the payload.
+pub const ALIGNED_HEADER_SIZE: usize = 32;
+
 /// Updatable compact vector in which each integer is represented in a fixed number of bits.
 ///
 /// # Memory usage
@@ -210,6 +238,204 @@ impl CompactVector {
         Ok(cv)
     }
 
+    /// Creates a new vector by taking the ownership of `chunks` storing fixed-width fields,
+    /// where the `i`-th integer is stored in bits `[i * width, (i + 1) * width)`.
+    ///
+    /// The number of integers is `chunks.len() / width`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `chunks`: Bit vector of concatenated integers.
+    ///  - `width`: Number of bits used to store an integer.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    ///  - `width` is not in `1..=64`, or
+    ///  - `chunks.len()` is not a multiple of `width`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::bit_vectors::BitVector;
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let mut bv = BitVector::new();
+    /// bv.push_bits(5, 3)?;
+    /// bv.push_bits(2, 3)?;
+    ///
+    /// let cv = CompactVector::from_bit_vector(bv, 3)?;
+    /// assert_eq!(cv.len(), 2);
+    /// assert_eq!(cv.get_int(0), Some(5));
+    /// assert_eq!(cv.get_int(1), Some(2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_bit_vector(chunks: BitVector, width: usize) -> Result<Self> {
+        if !(1..=64).contains(&width) {
+            return Err(anyhow!("width must be in 1..=64, but got {width}."));
+        }
+        if chunks.len() % width != 0 {
+            return Err(anyhow!(
+                "chunks.len() must be a multiple of width={width}, but got {}.",
+                chunks.len()
+            ));
+        }
+        let len = chunks.len() / width;
+        Ok(Self { chunks, len, width })
+    }
+
+    /// Creates a new vector by flattening `rows` of the same length in row-major order,
+    /// returning it with the number of columns.
+    ///
+    /// The width of each element automatically fits to the maximum value in `rows`.
+    /// The `(i, j)`-th integer is stored at position `i * cols + j`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `rows`: Rows of integers.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `rows` have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let (cv, cols) = CompactVector::from_rows(&[vec![1, 2, 3], vec![4, 5, 6]])?;
+    /// assert_eq!(cols, 3);
+    /// assert_eq!(cv.width(), 3);
+    /// assert_eq!(cv.get_int(1 * cols + 2), Some(6));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_rows(rows: &[Vec<usize>]) -> Result<(Self, usize)> {
+        let cols = rows.first().map_or(0, |row| row.len());
+        let mut max_int = 0;
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != cols {
+                return Err(anyhow!(
+                    "rows must have the same length {cols}, but got {} at row {i}.",
+                    row.len()
+                ));
+            }
+            max_int = row.iter().fold(max_int, |m, &x| m.max(x));
+        }
+        if rows.len() * cols == 0 {
+            return Ok((Self::default(), cols));
+        }
+        let mut cv = Self::with_capacity(rows.len() * cols, utils::needed_bits(max_int))?;
+        for row in rows {
+            // NOTE: Pushing should be safe.
+            cv.extend(row.iter().cloned()).unwrap();
+        }
+        Ok((cv, cols))
+    }
+
+    /// Creates a new vector of the offsets of consecutive segments (e.g., in the CSR format),
+    /// i.e., `0, l_0, l_0 + l_1, ..., l_0 + ... + l_{n-1}` for segment lengths `l_i`.
+    ///
+    /// The resulting vector has `lengths.len() + 1` integers,
+    /// and the width fits to the total length.
+    ///
+    /// # Arguments
+    ///
+    ///  - `lengths`: Lengths of the segments.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the total length overflows [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let offsets = CompactVector::from_segment_lengths(&[2, 0, 3])?;
+    /// assert_eq!(offsets.iter().collect::<Vec<_>>(), vec![0, 2, 2, 5]);
+    /// assert_eq!(offsets.width(), 3);
+    /// assert_eq!(offsets.segment_bounds(2), Some((2, 5)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_segment_lengths(lengths: &[usize]) -> Result<Self> {
+        let total = lengths
+            .iter()
+            .try_fold(0usize, |acc, &l| acc.checked_add(l))
+            .ok_or_else(|| anyhow!("the total length must not overflow usize."))?;
+        let mut cv = Self::with_capacity(lengths.len() + 1, utils::needed_bits(total))?;
+        let mut offset = 0;
+        // Pushing should be safe.
+        cv.push_int(offset).unwrap();
+        for &l in lengths {
+            offset += l;
+            cv.push_int(offset).unwrap();
+        }
+        Ok(cv)
+    }
+
+    /// Returns the bounds `(start, end)` of the `i`-th segment, i.e.,
+    /// the `i`-th and `i+1`-th integers, or [`None`] if `self.len() <= i + 1`.
+    ///
+    /// This assumes the vector stores segment offsets such as built by
+    /// [`Self::from_segment_lengths()`].
+    ///
+    /// # Arguments
+    ///
+    ///  - `i`: Segment index.
+    ///
+    /// # Complexity
+    ///
+    /// Constant
+    pub fn segment_bounds(&self, i: usize) -> Option<(usize, usize)> {
+        Some((self.get_int(i)?, self.get_int(i.checked_add(1)?)?))
+    }
+
+    /// Creates a new vector of `len` integers generated from `pattern`.
+    ///
+    /// The width of each element automatically fits to the maximum generated value.
+    /// This is the inverse of [`Self::detect_pattern()`].
+    ///
+    /// For [`Pattern::Arithmetic`], the `i`-th integer is computed as `start + i * step`
+    /// with wrapping arithmetic modulo $`2^{64}`$, i.e., a sequence going below zero
+    /// or above [`usize::MAX`] wraps around instead of failing.
+    ///
+    /// # Arguments
+    ///
+    ///  - `pattern`: Pattern of integers.
+    ///  - `len`: Number of elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sucds::int_vectors::CompactVector;
+    /// use sucds::int_vectors::compact_vector::Pattern;
+    ///
+    /// let cv = CompactVector::from_pattern(Pattern::Arithmetic { start: 9, step: -3 }, 4);
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![9, 6, 3, 0]);
+    /// assert_eq!(cv.width(), 4);
+    /// ```
+    pub fn from_pattern(pattern: Pattern, len: usize) -> Self {
+        let (start, step) = match pattern {
+            Pattern::Constant(val) => (val, 0),
+            Pattern::Arithmetic { start, step } => (start, step),
+        };
+        let gen = |i: usize| start.wrapping_add((step as usize).wrapping_mul(i));
+        let max_int = (0..len).map(gen).max().unwrap_or(0);
+        // Creating and pushing should be safe.
+        let mut cv = Self::with_capacity(len, utils::needed_bits(max_int)).unwrap();
+        for i in 0..len {
+            cv.push_int(gen(i)).unwrap();
+        }
+        cv
+    }
+
     /// Returns the `pos`-th integer, or [`None`] if out of bounds.
     ///
     /// # Arguments
@@ -275,12 +501,7 @@ impl CompactVector {
                 self.len()
             ));
         }
-        if self.width() != 64 && val >> self.width() != 0 {
-            return Err(anyhow!(
-                "val must fit in self.width()={} bits, but got {val}.",
-                self.width()
-            ));
-        }
+        self.check_fit(val)?;
         // NOTE(kampersanda): set_bits should be safe.
         self.chunks
             .set_bits(pos * self.width, val, self.width)
@@ -317,12 +538,7 @@ impl CompactVector {
     /// ```
     #[inline(always)]
     pub fn push_int(&mut self, val: usize) -> Result<()> {
-        if self.width() != 64 && val >> self.width() != 0 {
-            return Err(anyhow!(
-                "val must fit in self.width()={} bits, but got {val}.",
-                self.width()
-            ));
-        }
+        self.check_fit(val)?;
         // NOTE(kampersanda): set_bits should be safe.
         self.chunks.push_bits(val, self.width).unwrap();
         self.len += 1;
@@ -365,7 +581,51 @@ impl CompactVector {
         Ok(())
     }
 
-    /// Creates an iterator for enumerating integers.
+    /// Checks if integer `val` can be represented in `self.width()` bits.
+    #[inline(always)]
+    pub(crate) fn check_fit(&self, val: usize) -> Result<()> {
+        if self.width() != 64 && val >> self.width() != 0 {
+            return Err(anyhow!(
+                "val must fit in self.width()={} bits, but got {val}.",
+                self.width()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Copies the integers in `range` of `other` to the positions from `dst` in word units.
+    ///
+    /// `other` must have the same width, and both the ranges must be within the bounds.
+    pub(crate) fn copy_range_from(&mut self, dst: usize, other: &Self, range: Range<usize>) {
+        debug_assert_eq!(self.width(), other.width());
+        debug_assert!(range.end <= other.len() && dst + range.len() <= self.len());
+        let width = self.width();
+        // NOTE: Copying should be safe since the ranges are within the bounds.
+        self.chunks
+            .copy_bits_from(
+                dst * width,
+                &other.chunks,
+                range.start * width,
+                range.len() * width,
+            )
+            .unwrap();
+    }
+
+    /// Sets `val` to every integer satisfying `pred`, returning the number of positions changed,
+    /// i.e., those whose integer satisfies `pred` and is not already `val`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `pred`: Predicate on the current integers.
+    ///  - `val`: Integer value set.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `val` cannot be represent in `self.width()` bits.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
     ///
     /// # Examples
     ///
@@ -373,204 +633,3895 @@ impl CompactVector {
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use sucds::int_vectors::CompactVector;
     ///
-    /// let cv = CompactVector::from_slice(&[5, 256, 0])?;
-    /// let mut it = cv.iter();
+    /// let mut cv = CompactVector::from_slice(&[5, 2, 7, 1])?;
+    /// assert_eq!(cv.set_where(|x| x >= 5, 0)?, 2);
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![0, 2, 0, 1]);
     ///
-    /// assert_eq!(it.next(), Some(5));
-    /// assert_eq!(it.next(), Some(256));
-    /// assert_eq!(it.next(), Some(0));
-    /// assert_eq!(it.next(), None);
+    /// // The integer already equal to 2 is not counted.
+    /// assert_eq!(cv.set_where(|x| x <= 2, 2)?, 3);
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![2, 2, 2, 2]);
+    ///
+    /// assert_eq!(cv.replace_value(2, 3)?, 4);
+    /// assert_eq!(cv.replace_value(3, 3)?, 0);
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![3, 3, 3, 3]);
     /// # Ok(())
     /// # }
     /// ```
-    pub const fn iter(&self) -> Iter {
-        Iter::new(self)
-    }
-
-    /// Gets the number of integers.
-    #[inline(always)]
-    pub const fn len(&self) -> usize {
-        self.len
-    }
-
-    /// Checks if the vector is empty.
-    #[inline(always)]
-    pub const fn is_empty(&self) -> bool {
-        self.len() == 0
-    }
-
-    /// Returns the total number of integers it can hold without reallocating.
-    pub fn capacity(&self) -> usize {
-        self.chunks.capacity() / self.width()
-    }
-
-    /// Gets the number of bits to represent an integer.
-    #[inline(always)]
-    pub const fn width(&self) -> usize {
-        self.width
-    }
-}
-
-impl Build for CompactVector {
-    /// Creates a new vector from a slice of integers `vals`.
-    ///
-    /// This just calls [`Self::from_slice()`]. See the documentation.
-    fn build_from_slice<T>(vals: &[T]) -> Result<Self>
+    pub fn set_where<F>(&mut self, pred: F, val: usize) -> Result<usize>
     where
-        T: ToPrimitive,
-        Self: Sized,
+        F: Fn(usize) -> bool,
     {
-        Self::from_slice(vals)
+        self.check_fit(val)?;
+        let mut count = 0;
+        for i in 0..self.len() {
+            // NOTE: Getting and setting should be safe.
+            let x = self.get_int(i).unwrap();
+            if x != val && pred(x) {
+                self.set_int(i, val).unwrap();
+                count += 1;
+            }
+        }
+        Ok(count)
     }
-}
 
-impl NumVals for CompactVector {
-    /// Returns the number of integers stored (just wrapping [`Self::len()`]).
-    fn num_vals(&self) -> usize {
-        self.len()
+    /// Replaces every integer equal to `old` with `new`, returning the number of positions changed
+    /// (i.e., `0` if `old == new`).
+    ///
+    /// For small widths, integers in a word are compared with `old` at once
+    /// using broadword techniques.
+    ///
+    /// # Arguments
+    ///
+    ///  - `old`: Integer value replaced.
+    ///  - `new`: Integer value set.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `new` cannot be represent in `self.width()` bits.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    pub fn replace_value(&mut self, old: usize, new: usize) -> Result<usize> {
+        if self.check_fit(new).is_err() {
+            return Err(anyhow!(
+                "new must fit in self.width()={} bits, but got {new}.",
+                self.width()
+            ));
+        }
+        if self.is_empty() || old == new || self.check_fit(old).is_err() {
+            return Ok(0);
+        }
+        let width = self.width();
+        if width > BROADWORD_MATCH_MAX_WIDTH {
+            return self.set_where(|x| x == old, new);
+        }
+
+        let lanes = 64 / width;
+        let (mut lsbs, mut pattern) = (0, 0);
+        for j in 0..lanes {
+            lsbs |= 1 << (j * width);
+            pattern |= old << (j * width);
+        }
+        let msbs = lsbs << (width - 1);
+
+        let mut count = 0;
+        let mut pos = 0;
+        while pos < self.len() {
+            let k = lanes.min(self.len() - pos);
+            let mask = usize::MAX >> (64 - k * width);
+            // NOTE: Getting should be safe.
+            let diff = (self.chunks.get_bits(pos * width, k * width).unwrap() ^ pattern) & mask;
+            // The top bit of each lane is set iff the lane is nonzero, without carries across lanes.
+            let lows = !msbs & mask;
+            let nonzero = ((diff & lows) + lows) | diff;
+            let mut matched = !nonzero & msbs & mask;
+            while matched != 0 {
+                let j = matched.trailing_zeros() as usize / width;
+                // NOTE: Setting should be safe.
+                self.set_int(pos + j, new).unwrap();
+                count += 1;
+                matched &= matched - 1;
+            }
+            pos += k;
+        }
+        Ok(count)
     }
-}
 
-impl Access for CompactVector {
-    /// Returns the `pos`-th integer, or [`None`] if out of bounds
-    /// (just wrapping [`Self::get_int()`]).
+    /// Appends `count` copies of integer `val` at the end.
     ///
     /// # Arguments
     ///
-    ///  - `pos`: Position.
+    ///  - `count`: Number of integers appended.
+    ///  - `val`: Integer value appended.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `val` cannot be represent in `self.width()` bits.
     ///
     /// # Complexity
     ///
-    /// Constant
+    /// $`O(c)`$ for $`c`$ integers appended (Amortized)
     ///
     /// # Examples
     ///
     /// ```
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// use sucds::int_vectors::{CompactVector, Access};
+    /// use sucds::int_vectors::CompactVector;
     ///
-    /// let cv = CompactVector::from_slice(&[5, 256, 0])?;
-    /// assert_eq!(cv.access(0), Some(5));
-    /// assert_eq!(cv.access(1), Some(256));
-    /// assert_eq!(cv.access(2), Some(0));
-    /// assert_eq!(cv.access(3), None);
+    /// let mut cv = CompactVector::from_slice(&[5, 2])?;
+    /// cv.pad_end(2, 7)?;
+    /// cv.pad_start(3, 0)?;
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![0, 0, 0, 5, 2, 7, 7]);
     /// # Ok(())
     /// # }
-    fn access(&self, pos: usize) -> Option<usize> {
-        self.get_int(pos)
-    }
-}
-
-/// Iterator for enumerating integers, created by [`CompactVector::iter()`].
-pub struct Iter<'a> {
-    cv: &'a CompactVector,
-    pos: usize,
-}
-
-impl<'a> Iter<'a> {
-    /// Creates a new iterator.
-    pub const fn new(cv: &'a CompactVector) -> Self {
-        Self { cv, pos: 0 }
+    /// ```
+    pub fn pad_end(&mut self, count: usize, val: usize) -> Result<()> {
+        self.check_fit(val)?;
+        for _ in 0..count {
+            // NOTE: Pushing should be safe.
+            self.chunks.push_bits(val, self.width()).unwrap();
+        }
+        self.len += count;
+        Ok(())
     }
-}
+
+    /// Prepends `count` copies of integer `val` at the front,
+    /// shifting the existing integers by `count` positions.
+    ///
+    /// # Arguments
+    ///
+    ///  - `count`: Number of integers prepended.
+    ///  - `val`: Integer value prepended.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `val` cannot be represent in `self.width()` bits.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(c + n w / 64)`$ for $`c`$ integers prepended
+    pub fn pad_start(&mut self, count: usize, val: usize) -> Result<()> {
+        self.check_fit(val)?;
+        let num_bits = self.len() * self.width();
+        let pad_bits = count * self.width();
+        let mut chunks = BitVector::from_bit(false, pad_bits + num_bits);
+        for i in 0..count {
+            // NOTE: Setting should be safe.
+            chunks
+                .set_bits(i * self.width(), val, self.width())
+                .unwrap();
+        }
+        // NOTE: Copying should be safe since chunks has room for the original integers.
+        chunks
+            .copy_bits_from(pad_bits, &self.chunks, 0, num_bits)
+            .unwrap();
+        self.chunks = chunks;
+        self.len += count;
+        Ok(())
+    }
+
+    /// Rotates the order of integers in place such that the first `n % self.len()` integers
+    /// move to the end, in the same manner as [`slice::rotate_left()`].
+    ///
+    /// # Arguments
+    ///
+    ///  - `n`: Number of positions to rotate.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let mut cv = CompactVector::from_slice(&[1, 2, 3, 4, 5])?;
+    /// cv.rotate_left(2);
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![3, 4, 5, 1, 2]);
+    ///
+    /// cv.rotate_right(7);
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.is_empty() {
+            return;
+        }
+        let mid = n % self.len();
+        self.reverse_range(0, mid);
+        self.reverse_range(mid, self.len());
+        self.reverse_range(0, self.len());
+    }
+
+    /// Rotates the order of integers in place such that the last `n % self.len()` integers
+    /// move to the front, in the same manner as [`slice::rotate_right()`].
+    ///
+    /// # Arguments
+    ///
+    ///  - `n`: Number of positions to rotate.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.is_empty() {
+            return;
+        }
+        self.rotate_left(self.len() - n % self.len());
+    }
+
+    /// Reverses the integers in positions `start..end`.
+    fn reverse_range(&mut self, mut start: usize, mut end: usize) {
+        while start + 1 < end {
+            end -= 1;
+            // NOTE: Getting and setting should be safe.
+            let x = self.get_int(start).unwrap();
+            let y = self.get_int(end).unwrap();
+            self.set_int(start, y).unwrap();
+            self.set_int(end, x).unwrap();
+            start += 1;
+        }
+    }
+
+    /// Packs two vectors of the same length into a new vector of width `a.width() + b.width()`,
+    /// in which the `i`-th integer is `(a[i] << b.width()) | b[i]`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `a`: Vector of the upper fields.
+    ///  - `b`: Vector of the lower fields.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    ///  - `a.len() != b.len()`, or
+    ///  - `a.width() + b.width()` is not in `1..=64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let a = CompactVector::from_slice(&[1, 3])?;
+    /// let b = CompactVector::from_slice(&[4, 2])?;
+    /// let cv = CompactVector::pack_pairs(&a, &b)?;
+    ///
+    /// assert_eq!(cv.width(), 5);
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![0b01100, 0b11010]);
+    /// assert_eq!(cv.unpack_pairs(2, 3)?, (a, b));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pack_pairs(a: &Self, b: &Self) -> Result<Self> {
+        if a.len() != b.len() {
+            return Err(anyhow!(
+                "a.len() must be equal to b.len(), but got {} and {}.",
+                a.len(),
+                b.len()
+            ));
+        }
+        let width = a.width() + b.width();
+        if !(1..=64).contains(&width) {
+            return Err(anyhow!(
+                "a.width()+b.width() must be in 1..=64, but got {width}."
+            ));
+        }
+        let mut cv = Self::with_capacity(a.len(), width)?;
+        for (x, y) in a.iter().zip(b.iter()) {
+            // NOTE: Pushing should be safe.
+            cv.push_int((x << b.width()) | y).unwrap();
+        }
+        Ok(cv)
+    }
+
+    /// Unpacks the vector created by [`Self::pack_pairs()`] into the two original vectors
+    /// of widths `a_width` and `b_width`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `a_width`: Width of the upper fields.
+    ///  - `b_width`: Width of the lower fields.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    ///  - `a_width` or `b_width` is not in `1..=64`, or
+    ///  - `a_width + b_width != self.width()`.
+    pub fn unpack_pairs(&self, a_width: usize, b_width: usize) -> Result<(Self, Self)> {
+        if a_width.checked_add(b_width) != Some(self.width()) {
+            return Err(anyhow!(
+                "a_width+b_width must be equal to self.width()={}, but got {a_width}+{b_width}.",
+                self.width()
+            ));
+        }
+        let mut a = Self::with_capacity(self.len(), a_width)?;
+        let mut b = Self::with_capacity(self.len(), b_width)?;
+        // NOTE: b_width < 64 since a_width is positive.
+        let mask = (1 << b_width) - 1;
+        for x in self.iter() {
+            // NOTE: Pushing should be safe.
+            a.push_int(x >> b_width).unwrap();
+            b.push_int(x & mask).unwrap();
+        }
+        Ok((a, b))
+    }
+
+    /// Returns a new vector of the element-wise sums `self[i] + other[i]`
+    /// (e.g., for merging two count arrays).
+    ///
+    /// The width of each element automatically fits to the maximum sum.
+    ///
+    /// # Arguments
+    ///
+    ///  - `other`: Vector of the same length.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    ///  - `self.len() != other.len()`, or
+    ///  - a sum overflows [`usize`].
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let a = CompactVector::from_slice(&[3, 0, 7])?;
+    /// let b = CompactVector::from_slice(&[1, 2, 1])?;
+    /// let cv = a.add_elementwise(&b)?;
+    ///
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![4, 2, 8]);
+    /// assert_eq!(cv.width(), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_elementwise(&self, other: &Self) -> Result<Self> {
+        if self.len() != other.len() {
+            return Err(anyhow!(
+                "other.len() must be equal to self.len()={}, but got {}.",
+                self.len(),
+                other.len()
+            ));
+        }
+        let mut max_sum = 0;
+        for (i, (x, y)) in self.iter().zip(other.iter()).enumerate() {
+            let sum = x
+                .checked_add(y)
+                .ok_or_else(|| anyhow!("the sum at {i} must not overflow usize."))?;
+            max_sum = max_sum.max(sum);
+        }
+        let mut cv = Self::with_capacity(self.len(), utils::needed_bits(max_sum))?;
+        for (x, y) in self.iter().zip(other.iter()) {
+            // NOTE: Pushing should be safe.
+            cv.push_int(x + y).unwrap();
+        }
+        Ok(cv)
+    }
+
+    /// Splits the vector into `n` owned vectors of the same width whose lengths are nearly equal.
+    ///
+    /// Each chunk has `self.len() / n` or `self.len() / n + 1` integers,
+    /// where the remainder is absorbed by the last chunks.
+    /// Concatenating the chunks in order reproduces the original vector.
+    ///
+    /// # Arguments
+    ///
+    ///  - `n`: Number of chunks.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `n == 0`.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[1, 2, 3, 4, 5])?;
+    /// let chunks = cv.split_into(2)?;
+    ///
+    /// assert_eq!(chunks.len(), 2);
+    /// assert_eq!(chunks[0].iter().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(chunks[1].iter().collect::<Vec<_>>(), vec![3, 4, 5]);
+    /// assert_eq!(chunks[1].width(), cv.width());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn split_into(&self, n: usize) -> Result<Vec<Self>> {
+        if n == 0 {
+            return Err(anyhow!("n must not be zero."));
+        }
+        let (base, rem) = (self.len() / n, self.len() % n);
+        let mut chunks = Vec::with_capacity(n);
+        let mut pos = 0;
+        for i in 0..n {
+            let len = if i < n - rem { base } else { base + 1 };
+            let mut bits = BitVector::from_bit(false, len * self.width());
+            // Copying should be safe.
+            bits.copy_bits_from(0, &self.chunks, pos * self.width(), len * self.width())
+                .unwrap();
+            chunks.push(Self {
+                chunks: bits,
+                len,
+                width: self.width(),
+            });
+            pos += len;
+        }
+        Ok(chunks)
+    }
+
+    /// Returns the sums of all the consecutive `window` integers,
+    /// i.e., the `i`-th value is the sum of integers from position `i` to `i + window - 1`.
+    ///
+    /// The sums are computed incrementally in a single scan,
+    /// and the result is empty if `self.len() < window`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `window`: Number of integers in a window.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - `window == 0`, or
+    /// - a window sum overflows [`usize`].
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[1, 2, 3, 4, 5])?;
+    /// assert_eq!(cv.window_sums(2)?, vec![3, 5, 7, 9]);
+    /// assert_eq!(cv.window_sums(5)?, vec![15]);
+    /// assert!(cv.window_sums(6)?.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn window_sums(&self, window: usize) -> Result<Vec<usize>> {
+        if window == 0 {
+            return Err(anyhow!("window must not be zero."));
+        }
+        if self.len() < window {
+            return Ok(vec![]);
+        }
+        let mut sums = Vec::with_capacity(self.len() - window + 1);
+        let overflow = |pos| anyhow!("window sums must fit in usize, but overflowed at {pos}.");
+        let mut sum = 0usize;
+        for x in self.iter().take(window) {
+            sum = sum.checked_add(x).ok_or_else(|| overflow(0))?;
+        }
+        sums.push(sum);
+        for (i, (leaving, entering)) in self.iter().zip(self.iter().skip(window)).enumerate() {
+            // NOTE: Subtracting first never underflows since sum contains leaving,
+            // and reports overflow only when the next sum itself does not fit.
+            sum = (sum - leaving)
+                .checked_add(entering)
+                .ok_or_else(|| overflow(i + 1))?;
+            sums.push(sum);
+        }
+        Ok(sums)
+    }
+
+    /// Returns the polynomial rolling hashes of all the consecutive `window` integers,
+    /// i.e., the `i`-th value is the hash of integers from position `i` to `i + window - 1`.
+    ///
+    /// The hash of integers $`x_0, \dots, x_{w-1}`$ is
+    /// $`\sum_{j=0}^{w-1} (x_j + 1) B^{w-1-j} \bmod P`$, where
+    /// $`B`$ is [`ROLLING_HASH_BASE`] and $`P = 2^{61} - 1`$ is [`ROLLING_HASH_MODULUS`]
+    /// (and $`x_j + 1`$ is also taken modulo $`P`$).
+    /// The hashes are computed incrementally in a single scan,
+    /// and the result is empty if `self.len() < window`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `window`: Number of integers in a window.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `window == 0`.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[1, 2, 3, 1, 2])?;
+    /// let hashes = cv.rolling_hashes(2)?;
+    ///
+    /// assert_eq!(hashes.len(), 4);
+    /// assert_eq!(hashes[0], hashes[3]);
+    /// assert_ne!(hashes[0], hashes[1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rolling_hashes(&self, window: usize) -> Result<Vec<u64>> {
+        if window == 0 {
+            return Err(anyhow!("window must not be zero."));
+        }
+        if self.len() < window {
+            return Ok(vec![]);
+        }
+        let fold = |x: usize| (x as u64 % ROLLING_HASH_MODULUS + 1) % ROLLING_HASH_MODULUS;
+        let mul = |a: u64, b: u64| (a as u128 * b as u128 % ROLLING_HASH_MODULUS as u128) as u64;
+        let add = |a: u64, b: u64| (a + b) % ROLLING_HASH_MODULUS;
+
+        // B^{w-1}
+        let top = (1..window).fold(1, |acc, _| mul(acc, ROLLING_HASH_BASE));
+        let mut hashes = Vec::with_capacity(self.len() - window + 1);
+        let mut hash = self
+            .iter()
+            .take(window)
+            .fold(0, |h, x| add(mul(h, ROLLING_HASH_BASE), fold(x)));
+        hashes.push(hash);
+        for (leaving, entering) in self.iter().zip(self.iter().skip(window)) {
+            let removed = add(hash, ROLLING_HASH_MODULUS - mul(fold(leaving), top));
+            hash = add(mul(removed, ROLLING_HASH_BASE), fold(entering));
+            hashes.push(hash);
+        }
+        Ok(hashes)
+    }
+
+    /// Returns the start position and length of the longest strictly-increasing run
+    /// of consecutive integers.
+    ///
+    /// If there are multiple longest runs, the first one is returned.
+    /// `(0, 0)` is returned if the vector is empty.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 1, 2, 5, 5, 6])?;
+    /// assert_eq!(cv.longest_increasing_run(), (1, 3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn longest_increasing_run(&self) -> (usize, usize) {
+        let mut best = (0, 0);
+        let (mut start, mut prev) = (0, None);
+        for (i, x) in self.iter().enumerate() {
+            if prev.map_or(false, |p| x <= p) {
+                start = i;
+            }
+            if best.1 < i - start + 1 {
+                best = (start, i - start + 1);
+            }
+            prev = Some(x);
+        }
+        best
+    }
+
+    /// Replaces each integer with its image under a keyed permutation of `[0, 2^width)`,
+    /// which can be reverted by [`Self::invert_bijection()`] with the same `key`.
+    ///
+    /// Since the mapping is a bijection, distinct integers remain distinct and
+    /// the width is preserved. It is implemented by a four-round Feistel network
+    /// with cycle walking for odd widths.
+    ///
+    /// Note that this is obfuscation, not encryption;
+    /// it provides no cryptographic security.
+    ///
+    /// # Arguments
+    ///
+    ///  - `key`: Key of the permutation.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let mut cv = CompactVector::from_slice(&[5, 256, 0])?;
+    /// cv.apply_bijection(42);
+    /// assert_eq!(cv.width(), 9);
+    ///
+    /// cv.invert_bijection(42);
+    /// assert_eq!(cv.get_int(1), Some(256));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn apply_bijection(&mut self, key: u64) {
+        let feistel = Feistel::new(self.width(), key);
+        for i in 0..self.len() {
+            let x = feistel.forward(self.get_int(i).unwrap() as u64);
+            self.set_int(i, x as usize).unwrap();
+        }
+    }
+
+    /// Reverts [`Self::apply_bijection()`] with the same `key`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `key`: Key of the permutation.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    pub fn invert_bijection(&mut self, key: u64) {
+        let feistel = Feistel::new(self.width(), key);
+        for i in 0..self.len() {
+            let x = feistel.backward(self.get_int(i).unwrap() as u64);
+            self.set_int(i, x as usize).unwrap();
+        }
+    }
+
+    /// Returns the most frequent integer, or [`None`] if the vector is empty.
+    ///
+    /// If there are multiple most frequent integers, the smallest one is returned.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n + 2^w)`$ for width $`w`$ up to 16, or expected $`O(n)`$ otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 1, 3, 2, 1])?;
+    /// assert_eq!(cv.mode(), Some(1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn mode(&self) -> Option<usize> {
+        self.sorted_counts()
+            .into_iter()
+            .fold(None, |best: Option<(usize, usize)>, (x, c)| match best {
+                Some((_, bc)) if c <= bc => best,
+                _ => Some((x, c)),
+            })
+            .map(|(x, _)| x)
+    }
+
+    /// Returns the `num_buckets - 1` thresholds that split the sorted integers into `num_buckets`
+    /// buckets of nearly equal population (i.e., an equi-depth histogram).
+    ///
+    /// The `j`-th threshold is the `(j * n / num_buckets)`-th smallest integer for $`n`$ integers,
+    /// and the `j`-th bucket consists of integers in `thresholds[j-1]..thresholds[j]`.
+    /// If there are fewer distinct integers than buckets (or frequent integers),
+    /// a threshold can be repeated, meaning that the corresponding buckets are empty.
+    /// An empty vector is returned if the vector is empty.
+    ///
+    /// # Arguments
+    ///
+    ///  - `num_buckets`: Number of buckets.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `num_buckets == 0`.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n + 2^w + k)`$ for width $`w`$ up to 16, or $`O(n \lg n + k)`$ otherwise,
+    /// for $`k`$ buckets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[9, 1, 5, 3, 7, 2, 8, 4])?;
+    /// assert_eq!(cv.quantile_bounds(4)?, vec![3, 5, 8]);
+    ///
+    /// let cv = CompactVector::from_slice(&[1, 1, 1, 2])?;
+    /// assert_eq!(cv.quantile_bounds(4)?, vec![1, 1, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn quantile_bounds(&self, num_buckets: usize) -> Result<Vec<usize>> {
+        if num_buckets == 0 {
+            return Err(anyhow!("num_buckets must be positive, but got 0."));
+        }
+        if self.is_empty() {
+            return Ok(vec![]);
+        }
+        let n = self.len() as u128;
+        let mut bounds = Vec::with_capacity(num_buckets - 1);
+        let mut counts = self.sorted_counts().into_iter();
+        // NOTE: Fetching should be safe since the target is always less than self.len().
+        let (mut x, mut cum) = counts.next().unwrap();
+        for j in 1..num_buckets {
+            let target = (j as u128 * n / num_buckets as u128) as usize;
+            while cum <= target {
+                let (y, c) = counts.next().unwrap();
+                x = y;
+                cum += c;
+            }
+            bounds.push(x);
+        }
+        Ok(bounds)
+    }
+
+    /// Returns the pairs of distinct integers and their frequencies in increasing order of integers.
+    ///
+    /// Counters are kept in a dense array for small widths or in a hash map otherwise.
+    fn sorted_counts(&self) -> Vec<(usize, usize)> {
+        if self.width() <= DENSE_HISTOGRAM_MAX_WIDTH {
+            let mut counts = vec![0; 1 << self.width()];
+            self.iter().for_each(|x| counts[x] += 1);
+            counts
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, c)| c != 0)
+                .collect()
+        } else {
+            let mut counts = HashMap::new();
+            self.iter().for_each(|x| *counts.entry(x).or_insert(0) += 1);
+            let mut counts: Vec<_> = counts.into_iter().collect();
+            counts.sort_unstable();
+            counts
+        }
+    }
+
+    /// Returns the map from each distinct integer to the sorted positions where it occurs.
+    ///
+    /// Position lists are built in one pass, directly in a dense array for small widths.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n + 2^w)`$ for width $`w`$ up to 16, or expected $`O(n)`$ otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 1, 3, 2, 1])?;
+    /// let groups = cv.group_positions();
+    ///
+    /// assert_eq!(groups.len(), 3);
+    /// assert_eq!(groups[&1], vec![1, 4]);
+    /// assert_eq!(groups[&2], vec![3]);
+    /// assert_eq!(groups[&3], vec![0, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn group_positions(&self) -> HashMap<usize, Vec<usize>> {
+        if self.width() <= DENSE_HISTOGRAM_MAX_WIDTH {
+            let mut groups = vec![vec![]; 1 << self.width()];
+            self.iter().enumerate().for_each(|(i, x)| groups[x].push(i));
+            groups
+                .into_iter()
+                .enumerate()
+                .filter(|(_, g)| !g.is_empty())
+                .collect()
+        } else {
+            let mut groups = HashMap::new();
+            self.iter()
+                .enumerate()
+                .for_each(|(i, x)| groups.entry(x).or_insert_with(Vec::new).push(i));
+            groups
+        }
+    }
+
+    /// Returns the compact form of [`Self::group_positions()`].
+    ///
+    /// The first element stores the positions grouped by integer in a [`CompactVector`],
+    /// and the second element stores, for each distinct integer in increasing order,
+    /// the pair of the integer and the range of its sorted positions in the first element.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n \lg n + 2^w)`$ for width $`w`$ up to 16, or expected $`O(n \lg n)`$ otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 1, 3, 2, 1])?;
+    /// let (positions, groups) = cv.group_positions_compact();
+    ///
+    /// assert_eq!(positions.iter().collect::<Vec<_>>(), vec![1, 4, 3, 0, 2]);
+    /// assert_eq!(groups, vec![(1, 0..2), (2, 2..3), (3, 3..5)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn group_positions_compact(&self) -> (Self, Vec<(usize, Range<usize>)>) {
+        let mut groups = Vec::new();
+        let mut offset = 0;
+        for (x, c) in self.sorted_counts() {
+            groups.push((x, offset..offset + c));
+            offset += c;
+        }
+        // NOTE: Creating and setting should be safe.
+        let mut positions = Self::from_int(
+            0,
+            self.len(),
+            utils::needed_bits(self.len().saturating_sub(1)),
+        )
+        .unwrap();
+        let mut cursors: Vec<_> = groups.iter().map(|(_, r)| r.start).collect();
+        for (i, x) in self.iter().enumerate() {
+            let g = groups.binary_search_by_key(&x, |&(y, _)| y).unwrap();
+            positions.set_int(cursors[g], i).unwrap();
+            cursors[g] += 1;
+        }
+        (positions, groups)
+    }
+
+    /// Returns the bitmask of the distinct integers, where the `v`-th bit is set
+    /// if and only if `v` is stored, or [`None`] if `self.width() > 6`
+    /// (as the mask would not fit in [`u64`]).
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 1, 3, 5])?;
+    /// assert_eq!(cv.present_values_mask(), Some(0b101010));
+    ///
+    /// let cv = CompactVector::from_slice(&[64])?;
+    /// assert_eq!(cv.present_values_mask(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn present_values_mask(&self) -> Option<u64> {
+        if self.width() > 6 {
+            return None;
+        }
+        Some(self.iter().fold(0, |mask, x| mask | (1 << x)))
+    }
+
+    /// Builds the monotone minimal perfect hash function mapping each integer to its position,
+    /// assuming that the vector stores sorted distinct keys (just wrapping [`Mmphf::new()`]).
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - the integers are not strictly increasing, or
+    /// - the vector contains [`usize::MAX`].
+    pub fn build_mmphf(&self) -> Result<Mmphf> {
+        Mmphf::new(self)
+    }
+
+    /// Returns the positions of integers within Hamming distance `radius` from `query`,
+    /// i.e., differing from `query` in at most `radius` bits, in increasing order.
+    ///
+    /// # Arguments
+    ///
+    ///  - `query`: Integer to be compared.
+    ///  - `radius`: Maximum number of differing bits.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[0b101, 0b100, 0b010, 0b111])?;
+    /// assert_eq!(cv.hamming_neighbors(0b101, 0), vec![0]);
+    /// assert_eq!(cv.hamming_neighbors(0b101, 1), vec![0, 1, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn hamming_neighbors(&self, query: usize, radius: usize) -> Vec<usize> {
+        self.iter()
+            .enumerate()
+            .filter(|&(_, x)| ((x ^ query).count_ones() as usize) <= radius)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the number of leading integers that are equal between `self` and `other`.
+    ///
+    /// Integers are compared by value, so the widths can differ.
+    /// For the same widths, words are compared at once to find the first differing integer.
+    ///
+    /// # Arguments
+    ///
+    ///  - `other`: Vector to be compared.
+    ///
+    /// # Complexity
+    ///
+    /// Linear in the common prefix length
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let a = CompactVector::from_slice(&[1, 2, 3, 4])?;
+    /// let b = CompactVector::from_slice(&[1, 2, 5])?;
+    /// let c = CompactVector::from_int(1, 2, 20)?;
+    ///
+    /// assert_eq!(a.common_prefix_len(&b), 2);
+    /// assert_eq!(a.common_prefix_len(&c), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn common_prefix_len(&self, other: &Self) -> usize {
+        let n = self.len().min(other.len());
+        if self.width() != other.width() {
+            return self
+                .iter()
+                .zip(other.iter())
+                .take_while(|(x, y)| x == y)
+                .count();
+        }
+        let num_words = utils::ceiled_divide(n * self.width(), 64);
+        let (a, b) = (self.chunks.words(), other.chunks.words());
+        for i in 0..num_words {
+            let x = a[i] ^ b[i];
+            if x != 0 {
+                // NOTE: Bits out of the first n integers can differ.
+                let pos = i * 64 + x.trailing_zeros() as usize;
+                return n.min(pos / self.width());
+            }
+        }
+        n
+    }
+
+    /// Builds the range minimum query index over a copy of the vector
+    /// (just wrapping [`RmqIndex::new()`]).
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n \lg n)`$
+    pub fn build_rmq(&self) -> RmqIndex {
+        RmqIndex::new(self.clone())
+    }
+
+    /// Returns the positions of the `k` largest integers in decreasing order of integers,
+    /// where ties are broken by smaller positions.
+    ///
+    /// All the positions are returned if `self.len() <= k`.
+    ///
+    /// # Arguments
+    ///
+    ///  - `k`: Number of positions to be returned.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n \lg k)`$
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 9, 1, 9, 4])?;
+    /// assert_eq!(cv.top_k_positions(3), vec![1, 3, 4]);
+    /// assert_eq!(cv.top_k_positions(9), vec![1, 3, 4, 0, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn top_k_positions(&self, k: usize) -> Vec<usize> {
+        if k == 0 {
+            return vec![];
+        }
+        // Min-heap keeping the best k pairs, where a larger integer or a smaller position is better.
+        let mut heap = BinaryHeap::with_capacity(k.min(self.len()) + 1);
+        for (i, x) in self.iter().enumerate() {
+            heap.push(Reverse((x, Reverse(i))));
+            if k < heap.len() {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((_, Reverse(i)))| i)
+            .collect()
+    }
+
+    /// Returns the bitwise OR of all the integers, or `0` if the vector is empty.
+    ///
+    /// Note that this is computed over the integer values, not the raw bit stream.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[0b0011, 0b0110, 0b0100])?;
+    /// assert_eq!(cv.fold_or(), 0b0111);
+    /// assert_eq!(cv.fold_and(), 0b0000);
+    /// assert_eq!(cv.fold_xor(), 0b0001);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fold_or(&self) -> usize {
+        self.iter().fold(0, |acc, x| acc | x)
+    }
+
+    /// Returns the bitwise AND of all the integers, or `0` if the vector is empty.
+    ///
+    /// Note that this is computed over the integer values, not the raw bit stream.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    pub fn fold_and(&self) -> usize {
+        let mut it = self.iter();
+        it.next().map_or(0, |x| it.fold(x, |acc, x| acc & x))
+    }
+
+    /// Returns the bitwise XOR of all the integers, or `0` if the vector is empty.
+    ///
+    /// Note that this is computed over the integer values, not the raw bit stream.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    pub fn fold_xor(&self) -> usize {
+        self.iter().fold(0, |acc, x| acc ^ x)
+    }
+
+    /// Returns a hash value of the logical contents, i.e., the width, length, and integers.
+    ///
+    /// The hash value is stable across platforms and versions of this crate,
+    /// as it is independent of the backing words and computed by a fixed algorithm:
+    /// 64-bit FNV-1a over the little-endian `u64` encodings of the width, the length, and
+    /// each integer in order.
+    /// Note that vectors storing the same integers in different widths have different hash values.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[5, 256, 0])?;
+    /// assert_eq!(cv.content_hash(), 0x07c3e04c7a404085);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+        let fnv = |h: u64, x: usize| {
+            (x as u64)
+                .to_le_bytes()
+                .iter()
+                .fold(h, |h, &b| (h ^ u64::from(b)).wrapping_mul(FNV_PRIME))
+        };
+        let h = fnv(fnv(FNV_OFFSET_BASIS, self.width()), self.len());
+        self.iter().fold(h, fnv)
+    }
+
+    /// Returns the number of inversions, i.e., pairs of positions `(i, j)` such that
+    /// `i < j` and the `i`-th integer is greater than the `j`-th one.
+    ///
+    /// It is counted with a Fenwick tree over the value domain for widths up to 16,
+    /// or with merge sort otherwise.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n w + 2^w)`$ for width $`w`$ up to 16, or $`O(n \lg n)`$ otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 1, 2, 2])?;
+    /// assert_eq!(cv.count_inversions(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn count_inversions(&self) -> usize {
+        if self.width() <= DENSE_HISTOGRAM_MAX_WIDTH {
+            // Fenwick tree in which the x-th node counts the integers x-1 seen so far.
+            let universe = 1 << self.width();
+            let mut tree = vec![0; universe + 1];
+            let mut inversions = 0;
+            for (i, x) in self.iter().enumerate() {
+                // Counts the seen integers no greater than x.
+                let (mut j, mut cnt) = (x + 1, 0);
+                while j > 0 {
+                    cnt += tree[j];
+                    j &= j - 1;
+                }
+                inversions += i - cnt;
+                let mut j = x + 1;
+                while j <= universe {
+                    tree[j] += 1;
+                    j += j & j.wrapping_neg();
+                }
+            }
+            inversions
+        } else {
+            let mut vals: Vec<usize> = self.iter().collect();
+            let mut buf = vec![0; vals.len()];
+            Self::merge_count(&mut vals, &mut buf)
+        }
+    }
+
+    /// Sorts `vals` by merge sort, returning the number of inversions in `vals`.
+    fn merge_count(vals: &mut [usize], buf: &mut [usize]) -> usize {
+        if vals.len() <= 1 {
+            return 0;
+        }
+        let mid = vals.len() / 2;
+        let mut inversions = Self::merge_count(&mut vals[..mid], &mut buf[..mid])
+            + Self::merge_count(&mut vals[mid..], &mut buf[mid..]);
+        let (mut i, mut j) = (0, mid);
+        for b in buf[..vals.len()].iter_mut() {
+            if j == vals.len() || (i < mid && vals[i] <= vals[j]) {
+                *b = vals[i];
+                i += 1;
+            } else {
+                *b = vals[j];
+                inversions += mid - i;
+                j += 1;
+            }
+        }
+        vals.copy_from_slice(&buf[..vals.len()]);
+        inversions
+    }
+
+    /// Replaces each integer with the gap from its predecessor (the first one is unchanged),
+    /// narrowing the width to fit to the maximum gap.
+    ///
+    /// The original vector can be restored by [`Self::delta_decode()`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the integers are not sorted in increasing order.
+    /// In this case, the vector is not modified.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let mut cv = CompactVector::from_slice(&[250, 251, 253, 256])?;
+    /// cv.delta_encode()?;
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![250, 1, 2, 3]);
+    /// assert_eq!(cv.width(), 8);
+    ///
+    /// cv.delta_decode()?;
+    /// assert_eq!(cv.iter().collect::<Vec<_>>(), vec![250, 251, 253, 256]);
+    /// assert_eq!(cv.width(), 9);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delta_encode(&mut self) -> Result<()> {
+        let mut max_gap = 0;
+        for (i, (x, y)) in self.iter().zip(self.iter().skip(1)).enumerate() {
+            if y < x {
+                return Err(anyhow!(
+                    "integers must be sorted, but got {x} at {i} and {y} at {}.",
+                    i + 1
+                ));
+            }
+            max_gap = max_gap.max(y - x);
+        }
+        let first = self.get_int(0).unwrap_or(0);
+        let mut cv = Self::with_capacity(self.len(), utils::needed_bits(max_gap.max(first)))?;
+        let mut prev = 0;
+        for x in self.iter() {
+            // Pushing should be safe.
+            cv.push_int(x - prev).unwrap();
+            prev = x;
+        }
+        *self = cv;
+        Ok(())
+    }
+
+    /// Replaces each integer with the prefix sum up to it, reverting [`Self::delta_encode()`].
+    ///
+    /// The width is widened (or narrowed) to fit to the last prefix sum.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the total sum overflows [`usize`].
+    /// In this case, the vector is not modified.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    pub fn delta_decode(&mut self) -> Result<()> {
+        let total = self
+            .iter()
+            .try_fold(0usize, |acc, x| acc.checked_add(x))
+            .ok_or_else(|| anyhow!("the total sum must not overflow usize."))?;
+        let mut cv = Self::with_capacity(self.len(), utils::needed_bits(total))?;
+        let mut sum = 0;
+        for x in self.iter() {
+            sum += x;
+            // Pushing should be safe.
+            cv.push_int(sum).unwrap();
+        }
+        *self = cv;
+        Ok(())
+    }
+
+    /// Returns `k` integers at distinct positions selected uniformly at random,
+    /// or all the integers if `self.len() <= k`.
+    ///
+    /// The integers are sampled in a single scan by reservoir sampling.
+    /// Their order in the result is unspecified.
+    ///
+    /// This requires the `rand` feature.
+    ///
+    /// # Arguments
+    ///
+    ///  - `k`: Number of integers sampled.
+    ///  - `rng`: Random number generator.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[5, 256, 0, 7])?;
+    /// let sampled = cv.sample(2, &mut rand::thread_rng());
+    /// assert_eq!(sampled.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn sample<R>(&self, k: usize, rng: &mut R) -> Vec<usize>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        let mut reservoir = Vec::with_capacity(k.min(self.len()));
+        for (i, x) in self.iter().enumerate() {
+            if i < k {
+                reservoir.push(x);
+            } else {
+                let j = rng.gen_range(0..=i);
+                if j < k {
+                    reservoir[j] = x;
+                }
+            }
+        }
+        reservoir
+    }
+
+    /// Checks if two streams serialized by [`Serializable::serialize_into()`] represent
+    /// the same vector, i.e., the same width, length, and integers.
+    ///
+    /// The streams are compared word by word without deserializing the whole vectors,
+    /// returning on the first difference found.
+    /// Unused bits in the last backing word are ignored.
+    ///
+    /// # Arguments
+    ///
+    ///  - `a`: Reader of a serialized vector.
+    ///  - `b`: Reader of another serialized vector.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - reading fails, or
+    /// - the words cannot store the integers recorded in the headers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    /// use sucds::Serializable;
+    ///
+    /// let (mut a, mut b) = (vec![], vec![]);
+    /// CompactVector::from_slice(&[5, 256, 0])?.serialize_into(&mut a)?;
+    /// CompactVector::from_slice(&[5, 256, 1])?.serialize_into(&mut b)?;
+    ///
+    /// assert!(CompactVector::files_equal(&a[..], &a[..])?);
+    /// assert!(!CompactVector::files_equal(&a[..], &b[..])?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn files_equal<R, S>(mut a: R, mut b: S) -> Result<bool>
+    where
+        R: Read,
+        S: Read,
+    {
+        // The layout is (num_words, words, num_bits, len, width).
+        // Equal widths and lengths imply equal numbers of words.
+        let num_words = usize::deserialize_from(&mut a)?;
+        if num_words != usize::deserialize_from(&mut b)? {
+            return Ok(false);
+        }
+        let mut last = (0, 0);
+        for i in 0..num_words {
+            let (x, y) = (
+                usize::deserialize_from(&mut a)?,
+                usize::deserialize_from(&mut b)?,
+            );
+            // The last words are compared after knowing the number of meaningful bits.
+            if i + 1 == num_words {
+                last = (x, y);
+            } else if x != y {
+                return Ok(false);
+            }
+        }
+        let (len_a, width_a) = Self::deserialize_trailer(&mut a)?;
+        let (len_b, width_b) = Self::deserialize_trailer(&mut b)?;
+        if len_a != len_b || width_a != width_b {
+            return Ok(false);
+        }
+        // NOTE: Computed in u128 since the header values are untrusted and may overflow usize.
+        let num_bits = (len_a as u128) * (width_a as u128);
+        if (num_words as u128) * 64 < num_bits {
+            return Err(anyhow!(
+                "{num_words} words cannot store {len_a} integers of {width_a} bits."
+            ));
+        }
+        let rest = (num_bits % 64) as usize;
+        let mask = if rest == 0 {
+            usize::MAX
+        } else {
+            (1 << rest) - 1
+        };
+        Ok(last.0 & mask == last.1 & mask)
+    }
+
+    /// Deserializes the fields following the words, returning the length and width.
+    fn deserialize_trailer<R: Read>(mut reader: R) -> Result<(usize, usize)> {
+        let _num_bits = usize::deserialize_from(&mut reader)?;
+        let len = usize::deserialize_from(&mut reader)?;
+        let width = usize::deserialize_from(&mut reader)?;
+        Ok((len, width))
+    }
+
+    /// Returns the statistics of runs, i.e., maximal blocks of consecutive equal integers.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 3, 3, 1, 2, 2])?;
+    /// let stats = cv.run_stats();
+    /// assert_eq!(stats.num_runs, 3);
+    /// assert_eq!(stats.max_run_len, 3);
+    /// assert_eq!(stats.mean_run_len, 2.0);
+    /// assert_eq!(stats.frac_in_long_runs, 5.0 / 6.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_stats(&self) -> RunStats {
+        let (mut num_runs, mut max_run_len, mut num_in_long_runs) = (0, 0, 0);
+        let (mut prev, mut run_len) = (None, 0);
+        for x in self.iter().map(Some).chain([None]) {
+            if x == prev {
+                run_len += 1;
+                continue;
+            }
+            if run_len != 0 {
+                num_runs += 1;
+                max_run_len = max_run_len.max(run_len);
+                if run_len > 1 {
+                    num_in_long_runs += run_len;
+                }
+            }
+            prev = x;
+            run_len = 1;
+        }
+        if num_runs == 0 {
+            return RunStats::default();
+        }
+        RunStats {
+            num_runs,
+            max_run_len,
+            mean_run_len: self.len() as f64 / num_runs as f64,
+            frac_in_long_runs: num_in_long_runs as f64 / self.len() as f64,
+        }
+    }
+
+    /// Returns the bit vector of length `self.len()` marking the starts of runs,
+    /// where the `i`-th bit is set if and only if `i == 0` or the `i`-th integer differs from
+    /// the `(i-1)`-th one.
+    ///
+    /// Indexed with [`Rank9Sel`](crate::bit_vectors::Rank9Sel), `rank1(i + 1) - 1` gives
+    /// the index of the run containing position `i` in constant time.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::bit_vectors::{Rank, Rank9Sel};
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 3, 3, 1, 2, 2])?;
+    /// let bv = cv.run_boundary_bitmap();
+    /// assert_eq!(
+    ///     bv.iter().collect::<Vec<_>>(),
+    ///     vec![true, false, false, true, true, false]
+    /// );
+    ///
+    /// let bv = Rank9Sel::new(bv);
+    /// assert_eq!(bv.rank1(5 + 1).unwrap() - 1, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_boundary_bitmap(&self) -> BitVector {
+        let mut bv = BitVector::with_capacity(self.len());
+        let mut prev = None;
+        for x in self.iter() {
+            bv.push_bit(prev != Some(x));
+            prev = Some(x);
+        }
+        bv
+    }
+
+    /// Returns the vector in which each integer is replaced with its rank among the distinct integers
+    /// (i.e., coordinate compression), and the inverse table from ranks to the original integers.
+    ///
+    /// The resulting vector uses `needed_bits(d - 1)` bits for $`d`$ distinct integers.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n \lg d)`$ in addition to the histogram construction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[1000, 20, 1000, 300])?;
+    /// let (ranks, table) = cv.to_rank_space();
+    ///
+    /// assert_eq!(ranks.iter().collect::<Vec<_>>(), vec![2, 0, 2, 1]);
+    /// assert_eq!(ranks.width(), 2);
+    /// assert_eq!(table, vec![20, 300, 1000]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_rank_space(&self) -> (Self, Vec<usize>) {
+        let table: Vec<usize> = self.sorted_counts().into_iter().map(|(x, _)| x).collect();
+        // Creating and pushing should be safe.
+        let mut ranks = Self::with_capacity(
+            self.len(),
+            utils::needed_bits(table.len().saturating_sub(1)),
+        )
+        .unwrap();
+        for x in self.iter() {
+            ranks.push_int(table.binary_search(&x).unwrap()).unwrap();
+        }
+        (ranks, table)
+    }
+
+    /// Factorizes the vector into the sorted distinct integers and the codes into them
+    /// (i.e., dictionary encoding), such that `distinct.select(codes[i])` is the `i`-th integer.
+    ///
+    /// The codes are identical to the ranks of [`Self::to_rank_space()`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the vector contains [`usize::MAX`],
+    /// which cannot be stored in [`EliasFano`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[1000, 20, 1000, 300])?;
+    /// let (distinct, codes) = cv.factorize()?;
+    ///
+    /// assert_eq!(distinct.len(), 3);
+    /// assert_eq!(codes.iter().collect::<Vec<_>>(), vec![2, 0, 2, 1]);
+    /// assert_eq!(distinct.select(codes.get_int(0).unwrap()), Some(1000));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn factorize(&self) -> Result<(EliasFano, Self)> {
+        let (codes, table) = self.to_rank_space();
+        Ok((Self::sorted_into_elias_fano(&table)?, codes))
+    }
+
+    /// Returns the histogram in compressed form, i.e., the sorted distinct integers in [`EliasFano`]
+    /// and their frequencies in the parallel [`CompactVector`],
+    /// where the `k`-th frequency is of the integer `distinct.select(k)`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the vector contains [`usize::MAX`],
+    /// which cannot be stored in [`EliasFano`].
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n + 2^w)`$ for width $`w`$ up to 16, or expected $`O(n + d \lg d)`$ otherwise,
+    /// for $`d`$ distinct integers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[1000, 20, 1000, 300, 1000])?;
+    /// let (distinct, counts) = cv.compressed_histogram()?;
+    ///
+    /// assert_eq!(distinct.iter(0).collect::<Vec<_>>(), vec![20, 300, 1000]);
+    /// assert_eq!(counts.iter().collect::<Vec<_>>(), vec![1, 1, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compressed_histogram(&self) -> Result<(EliasFano, Self)> {
+        let (distinct, counts): (Vec<_>, Vec<_>) = self.sorted_counts().into_iter().unzip();
+        Ok((
+            Self::sorted_into_elias_fano(&distinct)?,
+            Self::from_slice(&counts)?,
+        ))
+    }
+
+    /// Builds [`EliasFano`] over strictly increasing integers `vals`.
+    fn sorted_into_elias_fano(vals: &[usize]) -> Result<EliasFano> {
+        EliasFano::from_sorted_tight(vals.iter().cloned(), vals.len(), vals.last().cloned())
+    }
+
+    /// Detects if the whole vector is constant or an arithmetic progression,
+    /// returning the pattern, or [`None`] if no pattern is found or the vector is empty.
+    ///
+    /// A vector of length one is detected as [`Pattern::Constant`].
+    /// Steps are computed in wrapping arithmetic on [`usize`], so the vector is exactly
+    /// reconstructed by [`Self::from_pattern()`] with its length.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    /// use sucds::int_vectors::compact_vector::Pattern;
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 5, 7, 9])?;
+    /// assert_eq!(cv.detect_pattern(), Some(Pattern::Arithmetic { start: 3, step: 2 }));
+    ///
+    /// let cv = CompactVector::from_slice(&[4, 4, 4])?;
+    /// assert_eq!(cv.detect_pattern(), Some(Pattern::Constant(4)));
+    ///
+    /// let cv = CompactVector::from_slice(&[3, 5, 8])?;
+    /// assert_eq!(cv.detect_pattern(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn detect_pattern(&self) -> Option<Pattern> {
+        let mut it = self.iter();
+        let start = it.next()?;
+        let mut prev = start;
+        let mut step = None;
+        for x in it {
+            let diff = x.wrapping_sub(prev) as isize;
+            if *step.get_or_insert(diff) != diff {
+                return None;
+            }
+            prev = x;
+        }
+        match step {
+            None | Some(0) => Some(Pattern::Constant(start)),
+            Some(step) => Some(Pattern::Arithmetic { start, step }),
+        }
+    }
+
+    /// Creates an iterator for enumerating integers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[5, 256, 0])?;
+    /// let mut it = cv.iter();
+    ///
+    /// assert_eq!(it.next(), Some(5));
+    /// assert_eq!(it.next(), Some(256));
+    /// assert_eq!(it.next(), Some(0));
+    /// assert_eq!(it.next(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn iter(&self) -> Iter<'_> {
+        Iter::new(self)
+    }
+
+    /// Creates an iterator for enumerating integers at positions
+    /// `start`, `start + stride`, `start + 2 * stride`, ... until the end
+    /// (e.g., a column of a row-major matrix).
+    ///
+    /// # Arguments
+    ///
+    ///  - `start`: Position of the first integer.
+    ///  - `stride`: Interval of positions.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `stride == 0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[1, 2, 3, 4, 5, 6, 7])?;
+    /// let it = cv.stride_iter(1, 3)?;
+    ///
+    /// assert_eq!(it.len(), 2);
+    /// assert_eq!(it.collect::<Vec<_>>(), vec![2, 5]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stride_iter(&self, start: usize, stride: usize) -> Result<StrideIter<'_>> {
+        if stride == 0 {
+            return Err(anyhow!("stride must not be zero."));
+        }
+        let remaining = if start < self.len() {
+            (self.len() - start - 1) / stride + 1
+        } else {
+            0
+        };
+        Ok(StrideIter {
+            cv: self,
+            pos: start,
+            stride,
+            remaining,
+        })
+    }
+
+    /// Gets the number of integers.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the vector is empty.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of integers it can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.chunks.capacity() / self.width()
+    }
+
+    /// Gets the number of bits to represent an integer.
+    #[inline(always)]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of bytes written by [`Serializable::serialize_into()`]
+    /// (just wrapping [`Serializable::size_in_bytes()`]).
+    pub fn serialized_size(&self) -> usize {
+        self.size_in_bytes()
+    }
+
+    /// Returns the number of bits per integer in the serialized form,
+    /// i.e., `self.serialized_size() * 8 / self.len()`,
+    /// or [`f64::NAN`] if the vector is empty.
+    ///
+    /// This includes the overhead of the header and the padding of the last word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_int(0, 64, 4)?;
+    /// // 4 words with 4 header fields.
+    /// assert_eq!(cv.bits_per_element(), 8.0);
+    /// assert!(CompactVector::new(4)?.bits_per_element().is_nan());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bits_per_element(&self) -> f64 {
+        if self.is_empty() {
+            return f64::NAN;
+        }
+        self.serialized_size() as f64 * 8.0 / self.len() as f64
+    }
+
+    /// Returns the number of bits per integer in the in-memory representation,
+    /// counting the allocated words of the bit chunks and the metadata fields,
+    /// or [`f64::NAN`] if the vector is empty.
+    ///
+    /// Unlike [`Self::bits_per_element()`], this includes the unused capacity.
+    pub fn bits_per_element_in_memory(&self) -> f64 {
+        if self.is_empty() {
+            return f64::NAN;
+        }
+        let bytes = std::mem::size_of::<Self>() + self.chunks.capacity() / 8;
+        bytes as f64 * 8.0 / self.len() as f64
+    }
+
+    /// Serializes the vector into the caller-provided buffer in the same format as
+    /// [`Serializable::serialize_into()`] without allocation, returning the number of bytes written.
+    ///
+    /// Bytes of `buf` after the returned size are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// - `buf`: Buffer of at least [`Self::serialized_size()`] bytes.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `buf.len() < self.serialized_size()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    /// use sucds::Serializable;
+    ///
+    /// let cv = CompactVector::from_slice(&[5, 256, 0])?;
+    /// let mut buf = [0; 64];
+    /// let size = cv.serialize_into_slice(&mut buf)?;
+    ///
+    /// assert_eq!(size, cv.serialized_size());
+    /// assert_eq!(CompactVector::deserialize_from(&buf[..size])?, cv);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize> {
+        let size = self.serialized_size();
+        if buf.len() < size {
+            return Err(anyhow!(
+                "buf must have at least {size} bytes, but got {}.",
+                buf.len()
+            ));
+        }
+        self.serialize_into(&mut buf[..size])
+    }
+
+    /// Exports the integers into the fixed-width little-endian buffer of an Apache Arrow
+    /// primitive array, returning the buffer and the number of integers.
+    ///
+    /// Each integer is stored as [`u32`] if `self.width() <= 32`, or [`u64`] otherwise
+    /// (i.e., the Arrow types `UInt32` or `UInt64`).
+    /// Validity bitmaps are not produced since all the integers are valid.
+    ///
+    /// # Complexity
+    ///
+    /// Linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[5, 256])?;
+    /// let (buf, len) = cv.to_arrow_primitive();
+    ///
+    /// assert_eq!(len, 2);
+    /// assert_eq!(buf, vec![5, 0, 0, 0, 0, 1, 0, 0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_arrow_primitive(&self) -> (Vec<u8>, usize) {
+        let num_bytes = if self.width() <= 32 { 4 } else { 8 };
+        let mut buf = Vec::with_capacity(self.len() * num_bytes);
+        for x in self.iter() {
+            // NOTE: The lowest bytes come first in little endian, so truncation gives u32 values.
+            buf.extend_from_slice(&(x as u64).to_le_bytes()[..num_bytes]);
+        }
+        (buf, self.len())
+    }
+
+    /// Writes each integer as an unsigned LEB128 varint into the writer,
+    /// returning the number of written bytes.
+    ///
+    /// Each byte holds seven bits of the integer from the lowest,
+    /// where the highest bit is set if more bytes follow.
+    ///
+    /// # Arguments
+    ///
+    /// - `writer`: [`Write`] variable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[2, 300])?;
+    /// let mut bytes = vec![];
+    /// let size = cv.write_leb128(&mut bytes)?;
+    ///
+    /// assert_eq!(size, 3);
+    /// assert_eq!(bytes, vec![0x02, 0xac, 0x02]);
+    ///
+    /// let other = CompactVector::from_leb128(&bytes[..], 2)?;
+    /// assert_eq!(other, cv);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_leb128<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let mut mem = 0;
+        let mut buf = [0; 10];
+        for mut x in self.iter() {
+            let mut n = 0;
+            loop {
+                let byte = (x & 0x7f) as u8;
+                x >>= 7;
+                if x == 0 {
+                    buf[n] = byte;
+                    n += 1;
+                    break;
+                }
+                buf[n] = byte | 0x80;
+                n += 1;
+            }
+            writer.write_all(&buf[..n])?;
+            mem += n;
+        }
+        Ok(mem)
+    }
+
+    /// Reads `count` unsigned LEB128 varints from the reader into a new vector.
+    ///
+    /// The width of each element automatically fits to the maximum value read,
+    /// in the same manner as [`Self::from_slice()`].
+    ///
+    /// # Arguments
+    ///
+    /// - `reader`: [`Read`] variable.
+    /// - `count`: Number of integers to be read.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - a varint overflows 64 bits, or
+    /// - reading fails (e.g., the reader ends before `count` varints).
+    pub fn from_leb128<R: Read>(mut reader: R, count: usize) -> Result<Self> {
+        // NOTE: count is not trusted, so the pre-allocation is capped.
+        let mut vals = Vec::with_capacity(count.min(1 << 16));
+        for _ in 0..count {
+            let mut x = 0;
+            let mut shift = 0;
+            loop {
+                let mut byte = [0; 1];
+                reader.read_exact(&mut byte)?;
+                let bits = usize::from(byte[0] & 0x7f);
+                if shift >= 64 || (shift == 63 && bits > 1) {
+                    return Err(anyhow!("a LEB128 varint must fit in 64 bits."));
+                }
+                x |= bits << shift;
+                if byte[0] & 0x80 == 0 {
+                    break;
+                }
+                shift += 7;
+            }
+            vals.push(x);
+        }
+        Self::from_slice(&vals)
+    }
+
+    /// Serializes the vector into the writer in the two-level layout,
+    /// returning the number of serialized bytes.
+    ///
+    /// Each integer is split into the high `h` bits and the low `self.width() - h` bits.
+    /// The low bits are stored densely as in [`CompactVector`], and the high bits are stored
+    /// in unary codes as in the upper layer of [`EliasFano`]
+    /// (i.e., the high value followed by a set bit), which can be indexed with select.
+    /// This is effective when integers of a wide vector are mostly small,
+    /// independently of whether they are sorted.
+    ///
+    /// # Size model
+    ///
+    /// For $`n`$ integers $`x_i`$ of width $`w`$ and $`\ell = w - h`$ low bits, the payload takes
+    ///
+    /// $`n \ell + n + \sum_{i} \lfloor x_i / 2^\ell \rfloor`$
+    ///
+    /// bits, and `h` in `0..=w` minimizing it is chosen.
+    /// Since `h == 0` results in $`n w + n`$ bits,
+    /// the overhead against the plain format is at most $`n`$ bits.
+    ///
+    /// # Layout
+    ///
+    /// The width, length, and `h` (as [`usize`]),
+    /// followed by the low bits and high bits (as [`BitVector`]).
+    ///
+    /// # Arguments
+    ///
+    /// - `writer`: [`Write`] variable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let mut cv = CompactVector::new(32)?;
+    /// cv.extend([3, 0, 5, 2, 1, 4, 1, 100])?;
+    ///
+    /// let mut bytes = vec![];
+    /// let size = cv.serialize_ef_into(&mut bytes)?;
+    /// assert_eq!(size, bytes.len());
+    ///
+    /// let other = CompactVector::deserialize_ef_from(&bytes[..])?;
+    /// assert_eq!(other, cv);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn serialize_ef_into<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let high_len = self.optimal_high_bits();
+        let low_len = self.width() - high_len;
+        let mut low_bits = BitVector::with_capacity(self.len() * low_len);
+        let mut high_bits = BitVector::new();
+        for x in self.iter() {
+            // NOTE: Pushing should be safe since the high bits are truncated.
+            low_bits.push_bits(x, low_len).unwrap();
+            let high = x.checked_shr(low_len as u32).unwrap_or(0);
+            (0..high).for_each(|_| high_bits.push_bit(false));
+            high_bits.push_bit(true);
+        }
+        let mut mem = self.width().serialize_into(&mut writer)?;
+        mem += self.len().serialize_into(&mut writer)?;
+        mem += high_len.serialize_into(&mut writer)?;
+        mem += low_bits.serialize_into(&mut writer)?;
+        mem += high_bits.serialize_into(&mut writer)?;
+        Ok(mem)
+    }
+
+    /// Deserializes the vector serialized by [`Self::serialize_ef_into()`].
+    ///
+    /// # Arguments
+    ///
+    /// - `reader`: [`Read`] variable.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - reading fails, or
+    /// - the fields are inconsistent.
+    pub fn deserialize_ef_from<R: Read>(mut reader: R) -> Result<Self> {
+        let width = usize::deserialize_from(&mut reader)?;
+        let len = usize::deserialize_from(&mut reader)?;
+        let high_len = usize::deserialize_from(&mut reader)?;
+        let low_bits = BitVector::deserialize_from(&mut reader)?;
+        let high_bits = BitVector::deserialize_from(&mut reader)?;
+        if width == 0 && len == 0 {
+            return Ok(Self::default());
+        }
+        if width < high_len {
+            return Err(anyhow!(
+                "high_len must be no greater than width={width}, but got {high_len}."
+            ));
+        }
+        let low_len = width - high_len;
+        let num_low_bits = len
+            .checked_mul(low_len)
+            .ok_or_else(|| anyhow!("len*low_len must fit in usize, but got {len}*{low_len}."))?;
+        if low_bits.len() != num_low_bits {
+            return Err(anyhow!(
+                "low bits must consist of {num_low_bits} bits, but got {}.",
+                low_bits.len()
+            ));
+        }
+        if high_bits.num_ones() < len {
+            return Err(anyhow!("high bits must consist of {len} unary codes."));
+        }
+        let mut cv = Self::with_capacity(len, width)?;
+        if len == 0 {
+            return Ok(cv);
+        }
+        let mut ones = high_bits.unary_iter(0);
+        let mut start = 0;
+        for i in 0..len {
+            // NOTE: Getting should be safe since the number of ones is checked.
+            let end = ones.next().unwrap();
+            let high = end - start;
+            start = end + 1;
+            if high_len < 64 && high >> high_len != 0 {
+                return Err(anyhow!(
+                    "high value must fit in {high_len} bits, but got {high}."
+                ));
+            }
+            // NOTE: Getting should be safe since the length is checked.
+            let low = low_bits.get_bits(i * low_len, low_len).unwrap();
+            cv.push_int(high.checked_shl(low_len as u32).unwrap_or(0) | low)?;
+        }
+        Ok(cv)
+    }
+
+    /// Returns the number of high bits `h` minimizing the payload size of
+    /// [`Self::serialize_ef_into()`] (the smallest one if there are multiple).
+    ///
+    /// The size model is computed from the number $`c_b`$ of integers whose `b`-th bit is set,
+    /// since $`\sum_{i} \lfloor x_i / 2^\ell \rfloor = \sum_{b \geq \ell} c_b 2^{b - \ell}`$.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n w + w^2)`$
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let mut cv = CompactVector::new(32)?;
+    /// cv.extend([3, 0, 2, 1, 3, 1])?;
+    /// assert_eq!(cv.optimal_high_bits(), 31);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn optimal_high_bits(&self) -> usize {
+        let mut counts = vec![0u128; self.width()];
+        for x in self.iter() {
+            let mut x = x;
+            while x != 0 {
+                counts[x.trailing_zeros() as usize] += 1;
+                x &= x - 1;
+            }
+        }
+        let n = self.len() as u128;
+        let mut best = (u128::MAX, 0);
+        for high_len in 0..=self.width() {
+            let low_len = self.width() - high_len;
+            let highs: u128 = counts[low_len..]
+                .iter()
+                .enumerate()
+                .map(|(d, &c)| c << d)
+                .sum();
+            let size = n * (low_len as u128 + 1) + highs;
+            if size < best.0 {
+                best = (size, high_len);
+            }
+        }
+        best.1
+    }
+
+    /// Serializes the vector into the writer in the aligned layout,
+    /// returning the number of serialized bytes.
+    ///
+    /// Unlike [`Serializable::serialize_into()`], a fixed-size header precedes the raw words
+    /// so that the payload starts at an 8-byte boundary from the beginning of the output.
+    /// This allows external tools to map the output into memory and read the payload in place
+    /// (e.g., via [`Self::view_from_aligned()`]).
+    ///
+    /// # Layout
+    ///
+    /// All the integers are stored in little endian.
+    ///
+    /// | Offset | Size | Field |
+    /// | --- | --- | --- |
+    /// | 0 | 8 | Magic number [`ALIGNED_MAGIC`] |
+    /// | 8 | 4 | Format version [`ALIGNED_VERSION`] (`u32`) |
+    /// | 12 | 4 | Width in bits (`u32`) |
+    /// | 16 | 8 | Number of integers (`u64`) |
+    /// | 24 | 8 | Payload length in bytes (`u64`) |
+    /// | 32 | Payload length | Raw words of the bit chunks (`u64` each) |
+    ///
+    /// The header consists of [`ALIGNED_HEADER_SIZE`] bytes.
+    /// The `i`-th integer is stored in bits `[i * width, (i + 1) * width)` of the payload,
+    /// where the `j`-th bit is the `j % 64`-th lowest bit of the `j / 64`-th word.
+    ///
+    /// # Arguments
+    ///
+    /// - `writer`: [`Write`] variable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    /// use sucds::int_vectors::compact_vector::ALIGNED_HEADER_SIZE;
+    ///
+    /// let cv = CompactVector::from_slice(&[5, 256, 0])?;
+    /// let mut bytes = vec![];
+    /// let size = cv.serialize_aligned_into(&mut bytes)?;
+    ///
+    /// assert_eq!(size, bytes.len());
+    /// assert_eq!(size, ALIGNED_HEADER_SIZE + 8);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn serialize_aligned_into<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let words = self.chunks.words();
+        writer.write_all(&ALIGNED_MAGIC)?;
+        let mut mem = ALIGNED_MAGIC.len();
+        mem += ALIGNED_VERSION.serialize_into(&mut writer)?;
+        mem += (self.width as u32).serialize_into(&mut writer)?;
+        mem += (self.len as u64).serialize_into(&mut writer)?;
+        mem += ((words.len() * 8) as u64).serialize_into(&mut writer)?;
+        debug_assert_eq!(mem, ALIGNED_HEADER_SIZE);
+        for &w in words {
+            mem += (w as u64).serialize_into(&mut writer)?;
+        }
+        Ok(mem)
+    }
+
+    /// Creates a read-only view of the vector serialized by [`Self::serialize_aligned_into()`],
+    /// without copying the payload.
+    ///
+    /// # Arguments
+    ///
+    /// - `bytes`: Bytes serialized by [`Self::serialize_aligned_into()`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - `bytes` is shorter than the header or the payload length recorded in it,
+    /// - the magic number or the version mismatches, or
+    /// - the recorded width and length are inconsistent with the payload
+    ///   (including when their product overflows).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::CompactVector;
+    ///
+    /// let cv = CompactVector::from_slice(&[5, 256, 0])?;
+    /// let mut bytes = vec![];
+    /// cv.serialize_aligned_into(&mut bytes)?;
+    ///
+    /// let view = CompactVector::view_from_aligned(&bytes)?;
+    /// assert_eq!(view.len(), 3);
+    /// assert_eq!(view.width(), 9);
+    /// assert_eq!(view.get_int(1), Some(256));
+    /// assert_eq!(view.get_int(3), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn view_from_aligned(bytes: &[u8]) -> Result<View<'_>> {
+        if bytes.len() < ALIGNED_HEADER_SIZE {
+            return Err(anyhow!(
+                "bytes must consist of at least {ALIGNED_HEADER_SIZE} bytes, but got {}.",
+                bytes.len()
+            ));
+        }
+        let mut header = &bytes[..ALIGNED_HEADER_SIZE];
+        let mut magic = [0; 8];
+        header.read_exact(&mut magic)?;
+        if magic != ALIGNED_MAGIC {
+            return Err(anyhow!("bytes must start with the magic number."));
+        }
+        let version = u32::deserialize_from(&mut header)?;
+        if version != ALIGNED_VERSION {
+            return Err(anyhow!(
+                "version must be {ALIGNED_VERSION}, but got {version}."
+            ));
+        }
+        let width = u32::deserialize_from(&mut header)? as usize;
+        let len = u64::deserialize_from(&mut header)? as usize;
+        let payload_len = u64::deserialize_from(&mut header)? as usize;
+        let payload = &bytes[ALIGNED_HEADER_SIZE..];
+        if payload.len() < payload_len {
+            return Err(anyhow!(
+                "payload must consist of {payload_len} bytes, but got {}.",
+                payload.len()
+            ));
+        }
+        // NOTE: A width of 0 is recorded for empty vectors such as CompactVector::default().
+        if 64 < width || (width == 0 && len != 0) {
+            return Err(anyhow!(
+                "width must be in 1..=64 (or 0 for an empty vector), but got {width}."
+            ));
+        }
+        let fits = match (payload_len.checked_mul(8), len.checked_mul(width)) {
+            (Some(payload_bits), Some(needed_bits)) => needed_bits <= payload_bits,
+            _ => false,
+        };
+        if payload_len % 8 != 0 || !fits {
+            return Err(anyhow!(
+                "payload of {payload_len} bytes cannot store {len} integers of {width} bits."
+            ));
+        }
+        Ok(View {
+            payload: &payload[..payload_len],
+            len,
+            width,
+        })
+    }
+}
+
+impl Build for CompactVector {
+    /// Creates a new vector from a slice of integers `vals`.
+    ///
+    /// This just calls [`Self::from_slice()`]. See the documentation.
+    fn build_from_slice<T>(vals: &[T]) -> Result<Self>
+    where
+        T: ToPrimitive,
+        Self: Sized,
+    {
+        Self::from_slice(vals)
+    }
+}
+
+impl NumVals for CompactVector {
+    /// Returns the number of integers stored (just wrapping [`Self::len()`]).
+    fn num_vals(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Access for CompactVector {
+    /// Returns the `pos`-th integer, or [`None`] if out of bounds
+    /// (just wrapping [`Self::get_int()`]).
+    ///
+    /// # Arguments
+    ///
+    ///  - `pos`: Position.
+    ///
+    /// # Complexity
+    ///
+    /// Constant
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::int_vectors::{CompactVector, Access};
+    ///
+    /// let cv = CompactVector::from_slice(&[5, 256, 0])?;
+    /// assert_eq!(cv.access(0), Some(5));
+    /// assert_eq!(cv.access(1), Some(256));
+    /// assert_eq!(cv.access(2), Some(0));
+    /// assert_eq!(cv.access(3), None);
+    /// # Ok(())
+    /// # }
+    fn access(&self, pos: usize) -> Option<usize> {
+        self.get_int(pos)
+    }
+}
+
+const FEISTEL_ROUNDS: u64 = 4;
+
+/// Feistel network over `[0, 2^width)` used by [`CompactVector::apply_bijection()`].
+///
+/// A balanced network permutes `2 * half` bits, where `half = ceil(width / 2)`.
+/// If `width` is odd, values outside `[0, 2^width)` are mapped again (i.e., cycle walking)
+/// so that the permutation is closed in the domain.
+struct Feistel {
+    width: usize,
+    half: usize,
+    key: u64,
+}
+
+impl Feistel {
+    const fn new(width: usize, key: u64) -> Self {
+        Self {
+            width,
+            half: (width + 1) / 2,
+            key,
+        }
+    }
+
+    #[inline(always)]
+    const fn mask(bits: usize) -> u64 {
+        if bits < 64 {
+            (1 << bits) - 1
+        } else {
+            u64::MAX
+        }
+    }
+
+    #[inline(always)]
+    const fn round(&self, x: u64, r: u64) -> u64 {
+        // The finalizer of SplitMix64.
+        let mut z = x ^ self.key ^ r.wrapping_mul(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        (z ^ (z >> 31)) & Self::mask(self.half)
+    }
+
+    fn forward(&self, mut x: u64) -> u64 {
+        loop {
+            let (mut l, mut r) = (x >> self.half, x & Self::mask(self.half));
+            for i in 0..FEISTEL_ROUNDS {
+                let t = l ^ self.round(r, i);
+                l = r;
+                r = t;
+            }
+            x = (l << self.half) | r;
+            if x <= Self::mask(self.width) {
+                return x;
+            }
+        }
+    }
+
+    fn backward(&self, mut x: u64) -> u64 {
+        loop {
+            let (mut l, mut r) = (x >> self.half, x & Self::mask(self.half));
+            for i in (0..FEISTEL_ROUNDS).rev() {
+                let t = r ^ self.round(l, i);
+                r = l;
+                l = t;
+            }
+            x = (l << self.half) | r;
+            if x <= Self::mask(self.width) {
+                return x;
+            }
+        }
+    }
+}
+
+/// Pattern of a whole vector, detected by [`CompactVector::detect_pattern()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    /// All the integers are the same value.
+    Constant(usize),
+    /// The `i`-th integer is `start + i * step`.
+    Arithmetic {
+        /// The first integer.
+        start: usize,
+        /// Difference between consecutive integers.
+        step: isize,
+    },
+}
+
+/// Statistics of runs of equal integers, created by [`CompactVector::run_stats()`].
+///
+/// All the fields are zeros for an empty vector.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct RunStats {
+    /// Number of runs.
+    pub num_runs: usize,
+    /// Maximum length of runs.
+    pub max_run_len: usize,
+    /// Mean length of runs.
+    pub mean_run_len: f64,
+    /// Fraction of integers in runs of length greater than 1.
+    pub frac_in_long_runs: f64,
+}
+
+/// Iterator for enumerating integers, created by [`CompactVector::iter()`].
+pub struct Iter<'a> {
+    cv: &'a CompactVector,
+    pos: usize,
+}
+
+impl<'a> Iter<'a> {
+    /// Creates a new iterator.
+    pub const fn new(cv: &'a CompactVector) -> Self {
+        Self { cv, pos: 0 }
+    }
+}
 
 impl<'a> Iterator for Iter<'a> {
     type Item = usize;
 
-    #[inline(always)]
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.pos < self.cv.len() {
-            let x = self.cv.access(self.pos).unwrap();
-            self.pos += 1;
-            Some(x)
-        } else {
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos < self.cv.len() {
+            let x = self.cv.access(self.pos).unwrap();
+            self.pos += 1;
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.cv.len(), Some(self.cv.len()))
+    }
+}
+
+/// Iterator for enumerating integers at regular intervals,
+/// created by [`CompactVector::stride_iter()`].
+pub struct StrideIter<'a> {
+    cv: &'a CompactVector,
+    pos: usize,
+    stride: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for StrideIter<'a> {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let x = self.cv.access(self.pos).unwrap();
+        self.remaining -= 1;
+        if self.remaining != 0 {
+            self.pos += self.stride;
+        }
+        Some(x)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for StrideIter<'a> {}
+
+/// Read-only view of a vector serialized by [`CompactVector::serialize_aligned_into()`],
+/// created by [`CompactVector::view_from_aligned()`].
+///
+/// The payload is borrowed and decoded on each access, so no copy is made.
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a> {
+    payload: &'a [u8],
+    len: usize,
+    width: usize,
+}
+
+impl<'a> View<'a> {
+    /// Returns the `pos`-th integer, or [`None`] if out of bounds.
+    ///
+    /// # Arguments
+    ///
+    ///  - `pos`: Position.
+    ///
+    /// # Complexity
+    ///
+    /// Constant
+    pub fn get_int(&self, pos: usize) -> Option<usize> {
+        if self.len <= pos {
+            return None;
+        }
+        let bpos = pos * self.width;
+        let (block, shift) = (bpos / 64, bpos % 64);
+        let mask = if self.width < 64 {
+            (1 << self.width) - 1
+        } else {
+            usize::MAX
+        };
+        let bits = if shift + self.width <= 64 {
+            self.word(block) >> shift & mask
+        } else {
+            (self.word(block) >> shift) | (self.word(block + 1) << (64 - shift) & mask)
+        };
+        Some(bits)
+    }
+
+    /// Gets the number of integers.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the view is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the number of bits to represent an integer.
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline(always)]
+    fn word(&self, i: usize) -> usize {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(&self.payload[i * 8..(i + 1) * 8]);
+        u64::from_le_bytes(buf) as usize
+    }
+}
+
+impl std::fmt::Debug for CompactVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut ints = vec![0; self.len()];
+        for (i, b) in ints.iter_mut().enumerate() {
+            *b = self.access(i).unwrap();
+        }
+        f.debug_struct("CompactVector")
+            .field("ints", &ints)
+            .field("len", &self.len)
+            .field("width", &self.width)
+            .finish()
+    }
+}
+
+impl Serializable for CompactVector {
+    fn serialize_into<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let mut mem = self.chunks.serialize_into(&mut writer)?;
+        mem += self.len.serialize_into(&mut writer)?;
+        mem += self.width.serialize_into(&mut writer)?;
+        Ok(mem)
+    }
+
+    fn deserialize_from<R: Read>(mut reader: R) -> Result<Self> {
+        let chunks = BitVector::deserialize_from(&mut reader)?;
+        let len = usize::deserialize_from(&mut reader)?;
+        let width = usize::deserialize_from(&mut reader)?;
+        Ok(Self { chunks, len, width })
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        self.chunks.size_in_bytes() + usize::size_of().unwrap() * 2
+    }
+}
+
+/// Returns the maximum width of `vectors`, i.e., the width needed to hold any of their integers
+/// without scanning them, or `0` if `vectors` is empty.
+///
+/// # Arguments
+///
+/// - `vectors`: Vectors to be unified.
+///
+/// # Complexity
+///
+/// $`O(k)`$ for $`k`$ vectors
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sucds::int_vectors::{compact_vector, CompactVector};
+///
+/// let a = CompactVector::from_int(1, 3, 10)?;
+/// let b = CompactVector::from_slice(&[7, 2])?;
+///
+/// assert_eq!(compact_vector::common_width(&[&a, &b]), 10);
+/// assert_eq!(compact_vector::narrowest_common(&[&a, &b]), 3);
+/// # Ok(())
+/// # }
+/// ```
+pub fn common_width(vectors: &[&CompactVector]) -> usize {
+    vectors.iter().map(|cv| cv.width()).max().unwrap_or(0)
+}
+
+/// Returns the minimal width that fits every integer stored in `vectors`,
+/// which is no greater than [`common_width()`] for non-empty vectors.
+///
+/// Since a width must be positive, `1` is returned if no integer is stored.
+///
+/// # Arguments
+///
+/// - `vectors`: Vectors to be unified.
+///
+/// # Complexity
+///
+/// Linear in the total number of integers
+pub fn narrowest_common(vectors: &[&CompactVector]) -> usize {
+    let max_int = vectors.iter().flat_map(|cv| cv.iter()).max().unwrap_or(0);
+    utils::needed_bits(max_int)
+}
+
+/// Streams a serialized vector through the per-element transform `f` into `output`.
+///
+/// The input is a vector serialized by [`Serializable::serialize_into()`],
+/// and the transformed vector is written in the same format,
+/// returning the number of bytes written.
+/// Neither the input nor the output vector is materialized in memory.
+/// The input is read from its current position, which is left at the end of the vector.
+///
+/// Note that `input` must implement [`Seek`] in addition to [`Read`].
+/// The serialized layout stores the width and the length after the words,
+/// so the trailer has to be read before decoding without buffering the whole vector,
+/// and fitting the output width to the transformed values requires a second pass over the words.
+/// The output width can be either supplied by the caller or fitted automatically
+/// via `output_width`.
+///
+/// # Arguments
+///
+/// - `input`: Seekable reader of a serialized [`CompactVector`].
+/// - `f`: Transform applied to each integer.
+/// - `output_width`: Width of the output vector. If [`None`], it is fitted to the maximum transformed
+///   value by an additional pass over the input, meaning that `f` is called twice for each integer.
+/// - `output`: [`Write`] variable.
+///
+/// # Errors
+///
+/// An error is returned if
+///
+/// - `input` is not a valid serialized [`CompactVector`],
+/// - `output_width` is not in `1..=64`,
+/// - a transformed value cannot be represent in `output_width` bits, or
+/// - reading or writing fails.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::io::Cursor;
+/// use sucds::int_vectors::{compact_vector, CompactVector};
+/// use sucds::Serializable;
+///
+/// let cv = CompactVector::from_slice(&[7, 2, 3])?;
+/// let mut input = vec![];
+/// cv.serialize_into(&mut input)?;
+///
+/// let mut output = vec![];
+/// let size = compact_vector::transform_file(Cursor::new(input), |x| x + 1, None, &mut output)?;
+///
+/// let other = CompactVector::deserialize_from(&output[..])?;
+/// assert_eq!(size, output.len());
+/// assert_eq!(other.width(), 4);
+/// assert_eq!(other.iter().collect::<Vec<_>>(), vec![8, 3, 4]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn transform_file<R, F, W>(
+    mut input: R,
+    mut f: F,
+    output_width: Option<usize>,
+    mut output: W,
+) -> Result<usize>
+where
+    R: Read + Seek,
+    F: FnMut(usize) -> usize,
+    W: Write,
+{
+    // The layout is (num_words, words, num_bits, len, width), so the trailer is read first.
+    let num_words = usize::deserialize_from(&mut input)?;
+    let payload = input.stream_position()?;
+    let payload_bytes = num_words
+        .checked_mul(8)
+        .and_then(|b| i64::try_from(b).ok())
+        .ok_or_else(|| {
+            anyhow!(
+                "num_words must be no greater than {}, but got {num_words}.",
+                i64::MAX / 8
+            )
+        })?;
+    input.seek(SeekFrom::Current(payload_bytes))?;
+    let (len, width) = CompactVector::deserialize_trailer(&mut input)?;
+    let end = input.stream_position()?;
+    if len != 0 && !(1..=64).contains(&width) {
+        return Err(anyhow!("width must be in 1..=64, but got {width}."));
+    }
+    // NOTE: Compared in u128 since the header values are untrusted and may overflow usize.
+    if (num_words as u128) * 64 < (len as u128) * (width as u128) {
+        return Err(anyhow!(
+            "{num_words} words cannot store {len} integers of {width} bits."
+        ));
+    }
+
+    let output_width = if let Some(w) = output_width {
+        if !(1..=64).contains(&w) {
+            return Err(anyhow!("output_width must be in 1..=64, but got {w}."));
+        }
+        w
+    } else {
+        input.seek(SeekFrom::Start(payload))?;
+        let mut decoder = StreamDecoder::new(&mut input, width);
+        let mut max_int = 0;
+        for _ in 0..len {
+            max_int = max_int.max(f(decoder.next()?));
+        }
+        utils::needed_bits(max_int)
+    };
+
+    input.seek(SeekFrom::Start(payload))?;
+    let num_bits = len.checked_mul(output_width).ok_or_else(|| {
+        anyhow!("{len} integers of output_width={output_width} bits cannot be stored.")
+    })?;
+    let mut mem = utils::ceiled_divide(num_bits, 64).serialize_into(&mut output)?;
+    let mut decoder = StreamDecoder::new(&mut input, width);
+    let mut encoder = StreamEncoder::new(&mut output, output_width);
+    for _ in 0..len {
+        let x = f(decoder.next()?);
+        if output_width != 64 && x >> output_width != 0 {
+            return Err(anyhow!(
+                "transformed values must fit in output_width={output_width} bits, but got {x}."
+            ));
+        }
+        mem += encoder.push(x)?;
+    }
+    mem += encoder.finish()?;
+    mem += num_bits.serialize_into(&mut output)?;
+    mem += len.serialize_into(&mut output)?;
+    mem += output_width.serialize_into(&mut output)?;
+    input.seek(SeekFrom::Start(end))?;
+    Ok(mem)
+}
+
+/// Decoder of integers of `width` bits from a stream of serialized words.
+struct StreamDecoder<R> {
+    reader: R,
+    width: usize,
+    buf: u128,
+    avail: usize,
+}
+
+impl<R: Read> StreamDecoder<R> {
+    const fn new(reader: R, width: usize) -> Self {
+        Self {
+            reader,
+            width,
+            buf: 0,
+            avail: 0,
+        }
+    }
+
+    fn next(&mut self) -> Result<usize> {
+        if self.avail < self.width {
+            let w = u64::deserialize_from(&mut self.reader)?;
+            self.buf |= u128::from(w) << self.avail;
+            self.avail += 64;
+        }
+        let x = (self.buf & ((1 << self.width) - 1)) as usize;
+        self.buf >>= self.width;
+        self.avail -= self.width;
+        Ok(x)
+    }
+}
+
+/// Encoder of integers of `width` bits into a stream of serialized words.
+struct StreamEncoder<W> {
+    writer: W,
+    width: usize,
+    buf: u128,
+    used: usize,
+}
+
+impl<W: Write> StreamEncoder<W> {
+    const fn new(writer: W, width: usize) -> Self {
+        Self {
+            writer,
+            width,
+            buf: 0,
+            used: 0,
+        }
+    }
+
+    fn push(&mut self, x: usize) -> Result<usize> {
+        self.buf |= (x as u128) << self.used;
+        self.used += self.width;
+        if self.used < 64 {
+            return Ok(0);
+        }
+        let mem = (self.buf as u64).serialize_into(&mut self.writer)?;
+        self.buf >>= 64;
+        self.used -= 64;
+        Ok(mem)
+    }
+
+    fn finish(mut self) -> Result<usize> {
+        if self.used == 0 {
+            return Ok(0);
+        }
+        (self.buf as u64).serialize_into(&mut self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    use crate::bit_vectors::{NumBits, Rank, Rank9Sel};
+
+    fn gen_random_ints(len: usize, max: usize, seed: u64) -> Vec<usize> {
+        let mut rng = ChaChaRng::seed_from_u64(seed);
+        (0..len).map(|_| rng.gen_range(0..=max)).collect()
+    }
+
+    #[test]
+    fn test_new_oob_0() {
+        let e = CompactVector::new(0);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("width must be in 1..=64, but got 0.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_new_oob_65() {
+        let e = CompactVector::new(65);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("width must be in 1..=64, but got 65.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_with_capacity_oob_0() {
+        let e = CompactVector::with_capacity(0, 0);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("width must be in 1..=64, but got 0.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_with_capacity_oob_65() {
+        let e = CompactVector::with_capacity(0, 65);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("width must be in 1..=64, but got 65.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_int_oob_0() {
+        let e = CompactVector::from_int(0, 0, 0);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("width must be in 1..=64, but got 0.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_int_oob_65() {
+        let e = CompactVector::from_int(0, 0, 65);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("width must be in 1..=64, but got 65.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_int_unfit() {
+        let e = CompactVector::from_int(4, 0, 2);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("val must fit in width=2 bits, but got 4.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_slice_uncastable() {
+        let e = CompactVector::from_slice(&[u128::MAX]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("vals must consist only of values castable into usize.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_int_oob() {
+        let mut cv = CompactVector::from_int(0, 1, 2).unwrap();
+        let e = cv.set_int(1, 1);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("pos must be no greater than self.len()=1, but got 1.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_int_unfit() {
+        let mut cv = CompactVector::from_int(0, 1, 2).unwrap();
+        let e = cv.set_int(0, 4);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("val must fit in self.width()=2 bits, but got 4.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_push_int_unfit() {
+        let mut cv = CompactVector::new(2).unwrap();
+        let e = cv.push_int(4);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("val must fit in self.width()=2 bits, but got 4.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extend_unfit() {
+        let mut cv = CompactVector::new(2).unwrap();
+        let e = cv.extend([4]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("val must fit in self.width()=2 bits, but got 4.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_64b() {
+        let mut cv = CompactVector::new(64).unwrap();
+        cv.push_int(42).unwrap();
+        assert_eq!(cv.get_int(0), Some(42));
+        cv.set_int(0, 334).unwrap();
+        assert_eq!(cv.get_int(0), Some(334));
+    }
+
+    #[test]
+    fn test_64b_from_int() {
+        let cv = CompactVector::from_int(42, 1, 64).unwrap();
+        assert_eq!(cv.get_int(0), Some(42));
+    }
+
+    #[test]
+    fn test_serialize_aligned() {
+        let vals: Vec<usize> = (0..200).map(|i| (i * 7919) % 1000).collect();
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let mut bytes = vec![];
+        let size = cv.serialize_aligned_into(&mut bytes).unwrap();
+        assert_eq!(size, bytes.len());
+
+        // The payload must start at an 8-byte boundary.
+        assert_eq!(ALIGNED_HEADER_SIZE % 8, 0);
+        let mut payload_len = [0; 8];
+        payload_len.copy_from_slice(&bytes[24..32]);
+        let payload_offset = bytes.len() - u64::from_le_bytes(payload_len) as usize;
+        assert_eq!(payload_offset, ALIGNED_HEADER_SIZE);
+        assert_eq!(payload_offset % 8, 0);
+
+        let payload: Vec<u8> = cv
+            .chunks
+            .words()
+            .iter()
+            .flat_map(|&w| (w as u64).to_le_bytes())
+            .collect();
+        assert_eq!(&bytes[ALIGNED_HEADER_SIZE..], &payload[..]);
+
+        let view = CompactVector::view_from_aligned(&bytes).unwrap();
+        assert_eq!(view.len(), cv.len());
+        assert_eq!(view.width(), cv.width());
+        for (i, &x) in vals.iter().enumerate() {
+            assert_eq!(view.get_int(i), Some(x));
+        }
+        assert_eq!(view.get_int(vals.len()), None);
+    }
+
+    #[test]
+    fn test_serialize_aligned_empty() {
+        for cv in [
+            CompactVector::default(),
+            CompactVector::from_slice::<usize>(&[]).unwrap(),
+            CompactVector::new(7).unwrap(),
+        ] {
+            let mut bytes = vec![];
+            let size = cv.serialize_aligned_into(&mut bytes).unwrap();
+            assert_eq!(size, ALIGNED_HEADER_SIZE);
+            let view = CompactVector::view_from_aligned(&bytes).unwrap();
+            assert!(view.is_empty());
+            assert_eq!(view.width(), cv.width());
+            assert_eq!(view.get_int(0), None);
+        }
+    }
+
+    #[test]
+    fn test_view_from_aligned_overflow() {
+        let cv = CompactVector::from_slice(&[1, 2, 3]).unwrap();
+        let mut bytes = vec![];
+        cv.serialize_aligned_into(&mut bytes).unwrap();
+        // Overwrites the number of integers so that len * width overflows.
+        bytes[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        let e = CompactVector::view_from_aligned(&bytes);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "payload of 8 bytes cannot store {} integers of 2 bits.",
+                u64::MAX
+            ))
+        );
+    }
+
+    #[test]
+    fn test_view_from_aligned_short() {
+        let e = CompactVector::view_from_aligned(&[0; 8]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("bytes must consist of at least 32 bytes, but got 8.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_view_from_aligned_magic() {
+        let e = CompactVector::view_from_aligned(&[0; 32]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("bytes must start with the magic number.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_split_into() {
+        let vals: Vec<usize> = (0..103).map(|i| (i * 31) % 97).collect();
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        for n in [1, 2, 7, 10, 103, 150] {
+            let chunks = cv.split_into(n).unwrap();
+            assert_eq!(chunks.len(), n);
+            let min_len = chunks.iter().map(|c| c.len()).min().unwrap();
+            let max_len = chunks.iter().map(|c| c.len()).max().unwrap();
+            assert!(max_len - min_len <= 1);
+            let mut other = CompactVector::new(cv.width()).unwrap();
+            for c in &chunks {
+                assert_eq!(c.width(), cv.width());
+                other.extend(c.iter()).unwrap();
+            }
+            assert_eq!(cv, other);
+        }
+    }
+
+    #[test]
+    fn test_split_into_empty() {
+        for cv in [CompactVector::default(), CompactVector::new(5).unwrap()] {
+            let chunks = cv.split_into(3).unwrap();
+            assert_eq!(chunks.len(), 3);
+            for c in &chunks {
+                assert!(c.is_empty());
+                assert_eq!(c.width(), cv.width());
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_into_zero() {
+        let cv = CompactVector::from_slice(&[1, 2, 3]).unwrap();
+        let e = cv.split_into(0);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("n must not be zero.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_window_sums() {
+        let vals = gen_random_ints(1000, 1000, 13);
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        for window in [1, 2, 10, 999, 1000, 1001] {
+            let expected: Vec<usize> = vals.windows(window).map(|w| w.iter().sum()).collect();
+            assert_eq!(cv.window_sums(window).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_window_sums_wide() {
+        let mut cv = CompactVector::new(64).unwrap();
+        cv.extend([usize::MAX - 1, 1, usize::MAX - 1, 0, 1])
+            .unwrap();
+        assert_eq!(
+            cv.window_sums(2).unwrap(),
+            vec![usize::MAX, usize::MAX, usize::MAX - 1, 1]
+        );
+        let e = cv.window_sums(3);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("window sums must fit in usize, but overflowed at 0.".to_string())
+        );
+        let mut cv = CompactVector::new(64).unwrap();
+        cv.extend([1, usize::MAX - 1, 2]).unwrap();
+        let e = cv.window_sums(2);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("window sums must fit in usize, but overflowed at 1.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_window_sums_zero() {
+        let cv = CompactVector::from_slice(&[1, 2, 3]).unwrap();
+        let e = cv.window_sums(0);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("window must not be zero.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_longest_increasing_run() {
+        let cv = CompactVector::from_slice(&[1, 2, 3, 4]).unwrap();
+        assert_eq!(cv.longest_increasing_run(), (0, 4));
+        let cv = CompactVector::from_slice(&[4, 3, 2, 1]).unwrap();
+        assert_eq!(cv.longest_increasing_run(), (0, 1));
+        let cv = CompactVector::from_slice(&[5, 1, 2, 2, 3, 4, 6, 0, 1]).unwrap();
+        assert_eq!(cv.longest_increasing_run(), (3, 4));
+        let cv = CompactVector::from_slice(&[7]).unwrap();
+        assert_eq!(cv.longest_increasing_run(), (0, 1));
+        let cv = CompactVector::new(3).unwrap();
+        assert_eq!(cv.longest_increasing_run(), (0, 0));
+    }
+
+    #[test]
+    fn test_bijection_exhaustive() {
+        for width in 1..=10 {
+            let vals: Vec<usize> = (0..1 << width).collect();
+            let mut cv = CompactVector::from_slice(&vals).unwrap();
+            cv.apply_bijection(334);
+            assert_eq!(cv.width(), width);
+            let mut mapped: Vec<usize> = cv.iter().collect();
+            if width >= 4 {
+                assert_ne!(mapped, vals);
+            }
+            mapped.sort_unstable();
+            assert_eq!(mapped, vals);
+            cv.invert_bijection(334);
+            assert!(cv.iter().eq(vals.iter().cloned()));
+        }
+    }
+
+    #[test]
+    fn test_bijection_64b() {
+        let mut rng = ChaChaRng::seed_from_u64(13);
+        let vals: Vec<usize> = (0..1000).map(|_| rng.gen()).collect();
+        let mut cv = CompactVector::new(64).unwrap();
+        cv.extend(vals.iter().cloned()).unwrap();
+        cv.apply_bijection(u64::MAX);
+        cv.invert_bijection(u64::MAX);
+        assert!(cv.iter().eq(vals.iter().cloned()));
+    }
+
+    #[test]
+    fn test_mode() {
+        let cv = CompactVector::from_slice(&[4, 2, 4, 4, 1, 2]).unwrap();
+        assert_eq!(cv.mode(), Some(4));
+        let cv = CompactVector::from_slice(&[5, 2, 5, 2, 7]).unwrap();
+        assert_eq!(cv.mode(), Some(2));
+        let cv = CompactVector::from_slice(&[9, 8, 7, 6]).unwrap();
+        assert_eq!(cv.mode(), Some(6));
+        let cv = CompactVector::from_slice(&[1usize << 40, 3, 1 << 40]).unwrap();
+        assert_eq!(cv.mode(), Some(1 << 40));
+        let cv = CompactVector::from_slice(&[1usize << 40, 3, 1 << 40, 3]).unwrap();
+        assert_eq!(cv.mode(), Some(3));
+        let cv = CompactVector::new(3).unwrap();
+        assert_eq!(cv.mode(), None);
+    }
+
+    #[test]
+    fn test_transform_file() {
+        let vals = gen_random_ints(1000, 255, 13);
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let mut input = vec![];
+        cv.serialize_into(&mut input).unwrap();
+        input.extend([1, 2, 3]);
+
+        let mut cursor = std::io::Cursor::new(input);
+        let mut output = vec![];
+        let size = transform_file(&mut cursor, |x| x + 1, None, &mut output).unwrap();
+        assert_eq!(cursor.position() as usize, cv.size_in_bytes());
+
+        let other = CompactVector::deserialize_from(&output[..]).unwrap();
+        let expected: Vec<usize> = vals.iter().map(|&x| x + 1).collect();
+        assert_eq!(other, CompactVector::from_slice(&expected).unwrap());
+        assert_eq!(size, output.len());
+        assert_eq!(size, other.size_in_bytes());
+    }
+
+    #[test]
+    fn test_transform_file_64b() {
+        let vals = [u64::MAX as usize, 0, 42];
+        let mut cv = CompactVector::new(64).unwrap();
+        cv.extend(vals).unwrap();
+        let mut input = vec![];
+        cv.serialize_into(&mut input).unwrap();
+        let mut output = vec![];
+        transform_file(std::io::Cursor::new(input), |x| x, Some(64), &mut output).unwrap();
+        let other = CompactVector::deserialize_from(&output[..]).unwrap();
+        assert_eq!(cv, other);
+    }
+
+    #[test]
+    fn test_transform_file_unfit() {
+        let cv = CompactVector::from_slice(&[7, 2]).unwrap();
+        let mut input = vec![];
+        cv.serialize_into(&mut input).unwrap();
+        let e = transform_file(std::io::Cursor::new(input), |x| x + 1, Some(3), vec![]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("transformed values must fit in output_width=3 bits, but got 8.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_transform_file_overflow() {
+        let cv = CompactVector::from_slice(&[1, 2, 3]).unwrap();
+        let mut input = vec![];
+        cv.serialize_into(&mut input).unwrap();
+
+        let mut crafted = input.clone();
+        crafted[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let e = transform_file(std::io::Cursor::new(crafted), |x| x, None, vec![]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "num_words must be no greater than {}, but got {}.",
+                i64::MAX / 8,
+                u64::MAX
+            ))
+        );
+
+        let mut crafted = input;
+        crafted[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        let e = transform_file(std::io::Cursor::new(crafted), |x| x, None, vec![]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "1 words cannot store {} integers of 2 bits.",
+                u64::MAX
+            ))
+        );
+    }
+
+    #[test]
+    fn test_folds() {
+        let vals = gen_random_ints(1000, (1 << 20) - 1, 13);
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        assert_eq!(cv.fold_or(), vals.iter().fold(0, |acc, &x| acc | x));
+        assert_eq!(
+            cv.fold_and(),
+            vals.iter().fold(usize::MAX, |acc, &x| acc & x)
+        );
+        assert_eq!(cv.fold_xor(), vals.iter().fold(0, |acc, &x| acc ^ x));
+
+        let cv = CompactVector::from_slice(&[0b1110, 0b0111]).unwrap();
+        assert_eq!(cv.fold_and(), 0b0110);
+
+        let cv = CompactVector::new(3).unwrap();
+        assert_eq!((cv.fold_or(), cv.fold_and(), cv.fold_xor()), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let cv = CompactVector::from_slice(&[5, 256, 0]).unwrap();
+        assert_eq!(cv.content_hash(), 0x07c3e04c7a404085);
+
+        let mut other = CompactVector::with_capacity(100, 9).unwrap();
+        other.extend([5, 0, 0]).unwrap();
+        other.set_int(1, 256).unwrap();
+        assert_eq!(cv.content_hash(), other.content_hash());
+
+        let other = CompactVector::from_int(0, 0, 1).unwrap();
+        assert_eq!(other.content_hash(), 0x392209f14dea4c24);
+    }
+
+    #[test]
+    fn test_count_inversions() {
+        for (max, seed) in [(0, 1), (15, 2), ((1 << 16) - 1, 3), (1 << 40, 4)] {
+            let vals = gen_random_ints(300, max, seed);
+            let cv = CompactVector::from_slice(&vals).unwrap();
+            let mut expected = 0;
+            for i in 0..vals.len() {
+                for j in i + 1..vals.len() {
+                    if vals[i] > vals[j] {
+                        expected += 1;
+                    }
+                }
+            }
+            assert_eq!(cv.count_inversions(), expected);
+        }
+        let cv = CompactVector::new(3).unwrap();
+        assert_eq!(cv.count_inversions(), 0);
+    }
+
+    #[test]
+    fn test_segment_lengths() {
+        let lengths = gen_random_ints(100, 50, 13);
+        let offsets = CompactVector::from_segment_lengths(&lengths).unwrap();
+        assert_eq!(offsets.len(), lengths.len() + 1);
+        assert_eq!(
+            offsets.width(),
+            utils::needed_bits(lengths.iter().sum::<usize>())
+        );
+        let mut start = 0;
+        for (i, &l) in lengths.iter().enumerate() {
+            assert_eq!(offsets.segment_bounds(i), Some((start, start + l)));
+            start += l;
+        }
+        assert_eq!(offsets.segment_bounds(lengths.len()), None);
+    }
+
+    #[test]
+    fn test_from_segment_lengths_empty() {
+        let offsets = CompactVector::from_segment_lengths(&[]).unwrap();
+        assert_eq!(offsets.iter().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(offsets.segment_bounds(0), None);
+    }
+
+    #[test]
+    fn test_from_segment_lengths_overflow() {
+        let e = CompactVector::from_segment_lengths(&[usize::MAX, 1]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("the total length must not overflow usize.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_delta_encode() {
+        let mut vals = gen_random_ints(1000, 1 << 30, 13);
+        vals.sort_unstable();
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let mut other = cv.clone();
+        other.delta_encode().unwrap();
+        assert!(other.width() <= cv.width());
+        let mut prev = 0;
+        for (i, &x) in vals.iter().enumerate() {
+            assert_eq!(other.get_int(i), Some(x - prev));
+            prev = x;
+        }
+        other.delta_decode().unwrap();
+        assert_eq!(cv, other);
+    }
+
+    #[test]
+    fn test_delta_encode_unsorted() {
+        let mut cv = CompactVector::from_slice(&[1, 3, 2]).unwrap();
+        let e = cv.delta_encode();
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("integers must be sorted, but got 3 at 1 and 2 at 2.".to_string())
+        );
+        assert_eq!(cv, CompactVector::from_slice(&[1, 3, 2]).unwrap());
+    }
+
+    #[test]
+    fn test_delta_decode_overflow() {
+        let mut cv = CompactVector::new(64).unwrap();
+        cv.extend([usize::MAX, 1]).unwrap();
+        let e = cv.delta_decode();
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("the total sum must not overflow usize.".to_string())
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sample() {
+        let vals = gen_random_ints(1000, 1 << 20, 13);
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let mut rng = ChaChaRng::seed_from_u64(334);
+        for k in [0, 1, 10, 999] {
+            let sampled = cv.sample(k, &mut rng);
+            assert_eq!(sampled.len(), k);
+            for x in sampled {
+                assert!(vals.contains(&x));
+            }
+        }
+        assert_eq!(cv.sample(1000, &mut rng), vals);
+        assert_eq!(cv.sample(2000, &mut rng), vals);
+    }
+
+    #[test]
+    fn test_files_equal() {
+        let vals = gen_random_ints(1000, 1000, 13);
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let mut a = vec![];
+        cv.serialize_into(&mut a).unwrap();
+
+        // Same integers built in a different way.
+        let mut other = CompactVector::with_capacity(2000, cv.width()).unwrap();
+        other.extend(vals.iter().cloned()).unwrap();
+        let mut b = vec![];
+        other.serialize_into(&mut b).unwrap();
+        assert!(CompactVector::files_equal(&a[..], &b[..]).unwrap());
+
+        // Garbage in unused bits of the last word.
+        let last_word = 8 + (cv.chunks.num_words() - 1) * 8;
+        b[last_word + 7] |= 0x80;
+        assert!(CompactVector::files_equal(&a[..], &b[..]).unwrap());
+
+        other.set_int(500, vals[500] ^ 1).unwrap();
+        let mut b = vec![];
+        other.serialize_into(&mut b).unwrap();
+        assert!(!CompactVector::files_equal(&a[..], &b[..]).unwrap());
+
+        let mut wide = CompactVector::new(cv.width() + 1).unwrap();
+        wide.extend(vals.iter().cloned()).unwrap();
+        let mut b = vec![];
+        wide.serialize_into(&mut b).unwrap();
+        assert!(!CompactVector::files_equal(&a[..], &b[..]).unwrap());
+    }
+
+    #[test]
+    fn test_files_equal_overflow() {
+        let cv = CompactVector::from_slice(&[1, 2, 3]).unwrap();
+        let mut bytes = vec![];
+        cv.serialize_into(&mut bytes).unwrap();
+        bytes[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        let e = CompactVector::files_equal(&bytes[..], &bytes[..]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "1 words cannot store {} integers of 2 bits.",
+                u64::MAX
+            ))
+        );
+    }
+
+    #[test]
+    fn test_run_stats() {
+        let cv = CompactVector::from_slice(&[1, 2, 3, 4]).unwrap();
+        let stats = cv.run_stats();
+        assert_eq!(stats.num_runs, 4);
+        assert_eq!(stats.max_run_len, 1);
+        assert_eq!(stats.mean_run_len, 1.0);
+        assert_eq!(stats.frac_in_long_runs, 0.0);
+
+        let cv = CompactVector::from_int(5, 10, 3).unwrap();
+        let stats = cv.run_stats();
+        assert_eq!(stats.num_runs, 1);
+        assert_eq!(stats.max_run_len, 10);
+        assert_eq!(stats.mean_run_len, 10.0);
+        assert_eq!(stats.frac_in_long_runs, 1.0);
+
+        let cv = CompactVector::from_slice(&[0, 1, 1, 0, 0, 0, 2, 1]).unwrap();
+        let stats = cv.run_stats();
+        assert_eq!(stats.num_runs, 5);
+        assert_eq!(stats.max_run_len, 3);
+        assert_eq!(stats.mean_run_len, 8.0 / 5.0);
+        assert_eq!(stats.frac_in_long_runs, 5.0 / 8.0);
+
+        let cv = CompactVector::new(3).unwrap();
+        assert_eq!(cv.run_stats(), RunStats::default());
+    }
+
+    #[test]
+    fn test_to_rank_space() {
+        let vals: Vec<usize> = gen_random_ints(1000, 20, 13)
+            .into_iter()
+            .map(|x| x << 30)
+            .collect();
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let (ranks, table) = cv.to_rank_space();
+        assert_eq!(ranks.len(), cv.len());
+        assert_eq!(ranks.width(), utils::needed_bits(table.len() - 1));
+        assert!(ranks.iter().eq(vals
+            .iter()
+            .map(|&x| table.iter().position(|&y| y == x).unwrap())));
+        assert!(ranks.iter().map(|r| table[r]).eq(vals.iter().cloned()));
+
+        let (ranks, table) = CompactVector::new(3).unwrap().to_rank_space();
+        assert!(ranks.is_empty());
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_detect_pattern() {
+        let cv = CompactVector::from_int(6, 100, 3).unwrap();
+        assert_eq!(cv.detect_pattern(), Some(Pattern::Constant(6)));
+        assert_eq!(CompactVector::from_pattern(Pattern::Constant(6), 100), cv);
+
+        let vals: Vec<usize> = (0..100).map(|i| 5 + i * 3).collect();
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let pattern = cv.detect_pattern();
+        assert_eq!(pattern, Some(Pattern::Arithmetic { start: 5, step: 3 }));
+        assert_eq!(CompactVector::from_pattern(pattern.unwrap(), 100), cv);
+
+        let vals: Vec<usize> = (0..100).map(|i| 500 - i * 5).collect();
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let pattern = cv.detect_pattern();
+        assert_eq!(
+            pattern,
+            Some(Pattern::Arithmetic {
+                start: 500,
+                step: -5
+            })
+        );
+        assert_eq!(CompactVector::from_pattern(pattern.unwrap(), 100), cv);
+
+        let mut cv = CompactVector::new(64).unwrap();
+        cv.extend([0, usize::MAX]).unwrap();
+        let pattern = cv.detect_pattern();
+        assert_eq!(pattern, Some(Pattern::Arithmetic { start: 0, step: -1 }));
+        assert_eq!(CompactVector::from_pattern(pattern.unwrap(), 2), cv);
+
+        let cv = CompactVector::from_slice(&[1, 2, 3, 5]).unwrap();
+        assert_eq!(cv.detect_pattern(), None);
+        let cv = CompactVector::from_slice(&[7]).unwrap();
+        assert_eq!(cv.detect_pattern(), Some(Pattern::Constant(7)));
+        let cv = CompactVector::new(3).unwrap();
+        assert_eq!(cv.detect_pattern(), None);
+    }
+
+    #[test]
+    fn test_group_positions_random() {
+        for &max in &[20, 1 << 20] {
+            let vals = gen_random_ints(1000, max, 29);
+            let cv = CompactVector::from_slice(&vals).unwrap();
+            let groups = cv.group_positions();
+            let (positions, compact) = cv.group_positions_compact();
+
+            let mut distinct = vals.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(groups.len(), distinct.len());
+            assert_eq!(compact.len(), distinct.len());
+            assert_eq!(positions.len(), vals.len());
+
+            for (&x, (y, range)) in distinct.iter().zip(compact) {
+                let expected: Vec<_> = (0..vals.len()).filter(|&i| vals[i] == x).collect();
+                assert_eq!(groups[&x], expected);
+                assert_eq!(y, x);
+                assert_eq!(
+                    range
+                        .map(|j| positions.get_int(j).unwrap())
+                        .collect::<Vec<_>>(),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_positions_empty() {
+        let cv = CompactVector::new(3).unwrap();
+        assert!(cv.group_positions().is_empty());
+        let (positions, groups) = cv.group_positions_compact();
+        assert!(positions.is_empty());
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_common_width() {
+        let a = CompactVector::from_slice(&[1, 0, 3]).unwrap();
+        let b = CompactVector::from_int(5, 4, 20).unwrap();
+        let c = CompactVector::with_capacity(10, 33).unwrap();
+        let d = CompactVector::from_slice(&[1usize << 40, 0]).unwrap();
+
+        assert_eq!(common_width(&[]), 0);
+        assert_eq!(common_width(&[&a]), 2);
+        assert_eq!(common_width(&[&a, &b]), 20);
+        assert_eq!(common_width(&[&a, &b, &c]), 33);
+        assert_eq!(common_width(&[&c, &d]), 41);
+
+        assert_eq!(narrowest_common(&[]), 1);
+        assert_eq!(narrowest_common(&[&c]), 1);
+        assert_eq!(narrowest_common(&[&a]), 2);
+        assert_eq!(narrowest_common(&[&a, &b]), 3);
+        assert_eq!(narrowest_common(&[&a, &b, &c]), 3);
+        assert_eq!(narrowest_common(&[&c, &d, &a]), 41);
+    }
+
+    #[test]
+    fn test_leb128_known() {
+        let cv = CompactVector::from_slice(&[0, 1, 127, 128, 624485, u64::MAX as usize]).unwrap();
+        let mut bytes = vec![];
+        let size = cv.write_leb128(&mut bytes).unwrap();
+        let expected = vec![
+            0x00, 0x01, 0x7f, 0x80, 0x01, 0xe5, 0x8e, 0x26, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0x01,
+        ];
+        assert_eq!(bytes, expected);
+        assert_eq!(size, expected.len());
+        assert_eq!(CompactVector::from_leb128(&bytes[..], 6).unwrap(), cv);
+    }
+
+    #[test]
+    fn test_leb128_random() {
+        let mut rng = ChaChaRng::seed_from_u64(31);
+        for &width in &[1, 7, 8, 33, 64] {
+            let mut cv = CompactVector::new(width).unwrap();
+            for _ in 0..1000 {
+                cv.push_int(rng.gen::<usize>() >> (64 - width)).unwrap();
+            }
+            let mut bytes = vec![];
+            cv.write_leb128(&mut bytes).unwrap();
+            let other = CompactVector::from_leb128(&bytes[..], cv.len()).unwrap();
+            assert_eq!(
+                other.iter().collect::<Vec<_>>(),
+                cv.iter().collect::<Vec<_>>()
+            );
+            assert!(other.width() <= width);
+        }
+    }
+
+    #[test]
+    fn test_from_leb128_overflow() {
+        let bytes = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        let e = CompactVector::from_leb128(&bytes[..], 1);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("a LEB128 varint must fit in 64 bits.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_leb128_truncated() {
+        let bytes = [0x05, 0x80];
+        assert!(CompactVector::from_leb128(&bytes[..], 2).is_err());
+        assert!(CompactVector::from_leb128(&bytes[..], usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_present_values_mask() {
+        let vals = gen_random_ints(100, 15, 37);
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        assert_eq!(cv.width(), 4);
+        let mask = cv.present_values_mask().unwrap();
+        for v in 0..64 {
+            assert_eq!(mask >> v & 1 == 1, vals.contains(&v));
+        }
+
+        let cv = CompactVector::from_int(63, 3, 6).unwrap();
+        assert_eq!(cv.present_values_mask(), Some(1 << 63));
+        assert_eq!(
+            CompactVector::new(4).unwrap().present_values_mask(),
+            Some(0)
+        );
+        assert_eq!(CompactVector::new(7).unwrap().present_values_mask(), None);
+        assert_eq!(
+            CompactVector::from_int(1, 3, 64)
+                .unwrap()
+                .present_values_mask(),
             None
+        );
+    }
+
+    #[test]
+    fn test_rotate_random() {
+        let vals = gen_random_ints(100, 1000, 41);
+        for &n in &[0, 1, 37, 99, 100, 101, 250] {
+            let mut cv = CompactVector::from_slice(&vals).unwrap();
+            let mut expected = vals.clone();
+            cv.rotate_left(n);
+            expected.rotate_left(n % vals.len());
+            assert_eq!(cv.iter().collect::<Vec<_>>(), expected);
+            assert_eq!(cv.width(), 10);
+
+            let mut cv = CompactVector::from_slice(&vals).unwrap();
+            let mut expected = vals.clone();
+            cv.rotate_right(n);
+            expected.rotate_right(n % vals.len());
+            assert_eq!(cv.iter().collect::<Vec<_>>(), expected);
         }
     }
 
-    #[inline(always)]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.cv.len(), Some(self.cv.len()))
+    #[test]
+    fn test_rotate_empty() {
+        let mut cv = CompactVector::new(3).unwrap();
+        cv.rotate_left(3);
+        cv.rotate_right(1);
+        assert!(cv.is_empty());
     }
-}
 
-impl std::fmt::Debug for CompactVector {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut ints = vec![0; self.len()];
-        for (i, b) in ints.iter_mut().enumerate() {
-            *b = self.access(i).unwrap();
+    #[test]
+    fn test_run_boundary_bitmap() {
+        let mut rng = ChaChaRng::seed_from_u64(43);
+        let vals: Vec<usize> = (0..1000).map(|_| rng.gen_range(0..3)).collect();
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let bv = Rank9Sel::new(cv.run_boundary_bitmap());
+        assert_eq!(bv.num_bits(), vals.len());
+        assert_eq!(bv.num_ones(), cv.run_stats().num_runs);
+
+        let mut run = 0;
+        for i in 0..vals.len() {
+            if i != 0 && vals[i] != vals[i - 1] {
+                run += 1;
+            }
+            assert_eq!(bv.rank1(i + 1).unwrap() - 1, run);
         }
-        f.debug_struct("CompactVector")
-            .field("ints", &ints)
-            .field("len", &self.len)
-            .field("width", &self.width)
-            .finish()
+        assert!(CompactVector::new(3)
+            .unwrap()
+            .run_boundary_bitmap()
+            .is_empty());
     }
-}
 
-impl Serializable for CompactVector {
-    fn serialize_into<W: Write>(&self, mut writer: W) -> Result<usize> {
-        let mut mem = self.chunks.serialize_into(&mut writer)?;
-        mem += self.len.serialize_into(&mut writer)?;
-        mem += self.width.serialize_into(&mut writer)?;
-        Ok(mem)
+    #[test]
+    fn test_pack_pairs_round_trip() {
+        let mut rng = ChaChaRng::seed_from_u64(47);
+        for &(wa, wb) in &[(1, 1), (3, 5), (32, 32), (1, 63), (63, 1), (20, 7)] {
+            let mut a = CompactVector::new(wa).unwrap();
+            let mut b = CompactVector::new(wb).unwrap();
+            for _ in 0..100 {
+                a.push_int(rng.gen::<usize>() >> (64 - wa)).unwrap();
+                b.push_int(rng.gen::<usize>() >> (64 - wb)).unwrap();
+            }
+            let cv = CompactVector::pack_pairs(&a, &b).unwrap();
+            assert_eq!(cv.width(), wa + wb);
+            for i in 0..cv.len() {
+                assert_eq!(
+                    cv.get_int(i),
+                    Some((a.get_int(i).unwrap() << wb) | b.get_int(i).unwrap())
+                );
+            }
+            assert_eq!(cv.unpack_pairs(wa, wb).unwrap(), (a, b));
+        }
     }
 
-    fn deserialize_from<R: Read>(mut reader: R) -> Result<Self> {
-        let chunks = BitVector::deserialize_from(&mut reader)?;
-        let len = usize::deserialize_from(&mut reader)?;
-        let width = usize::deserialize_from(&mut reader)?;
-        Ok(Self { chunks, len, width })
+    #[test]
+    fn test_pack_pairs_diff_len() {
+        let a = CompactVector::from_slice(&[1, 2]).unwrap();
+        let b = CompactVector::from_slice(&[1]).unwrap();
+        let e = CompactVector::pack_pairs(&a, &b);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("a.len() must be equal to b.len(), but got 2 and 1.".to_string())
+        );
     }
 
-    fn size_in_bytes(&self) -> usize {
-        self.chunks.size_in_bytes() + usize::size_of().unwrap() * 2
+    #[test]
+    fn test_pack_pairs_too_wide() {
+        let a = CompactVector::new(60).unwrap();
+        let b = CompactVector::new(5).unwrap();
+        let e = CompactVector::pack_pairs(&a, &b);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("a.width()+b.width() must be in 1..=64, but got 65.".to_string())
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_unpack_pairs_wrong_widths() {
+        let cv = CompactVector::new(8).unwrap();
+        let e = cv.unpack_pairs(3, 4);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("a_width+b_width must be equal to self.width()=8, but got 3+4.".to_string())
+        );
+        let e = cv.unpack_pairs(usize::MAX, 9);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "a_width+b_width must be equal to self.width()=8, but got {}+9.",
+                usize::MAX
+            ))
+        );
+    }
 
     #[test]
-    fn test_new_oob_0() {
-        let e = CompactVector::new(0);
+    fn test_quantile_bounds_random() {
+        for &max in &[10, 1000, 1 << 30] {
+            let vals = gen_random_ints(1000, max, 59);
+            let cv = CompactVector::from_slice(&vals).unwrap();
+            let mut sorted = vals.clone();
+            sorted.sort_unstable();
+            for &k in &[1, 2, 3, 10, 999, 1000, 2000] {
+                let expected: Vec<_> = (1..k).map(|j| sorted[j * sorted.len() / k]).collect();
+                assert_eq!(cv.quantile_bounds(k).unwrap(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantile_bounds_empty() {
+        let cv = CompactVector::new(3).unwrap();
+        assert_eq!(cv.quantile_bounds(5).unwrap(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_quantile_bounds_zero() {
+        let cv = CompactVector::from_slice(&[1, 2]).unwrap();
+        let e = cv.quantile_bounds(0);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("width must be in 1..=64, but got 0.".to_string())
+            Some("num_buckets must be positive, but got 0.".to_string())
         );
     }
 
     #[test]
-    fn test_new_oob_65() {
-        let e = CompactVector::new(65);
+    fn test_add_elementwise_random() {
+        for &(max_a, max_b) in &[(1, 1), (10, 1000), (1 << 40, 5), (1 << 62, 1 << 62)] {
+            let a = gen_random_ints(300, max_a, 61);
+            let b = gen_random_ints(300, max_b, 67);
+            let expected: Vec<_> = a.iter().zip(&b).map(|(x, y)| x + y).collect();
+            let cv = CompactVector::from_slice(&a)
+                .unwrap()
+                .add_elementwise(&CompactVector::from_slice(&b).unwrap())
+                .unwrap();
+            assert_eq!(cv.iter().collect::<Vec<_>>(), expected);
+            assert_eq!(
+                cv.width(),
+                utils::needed_bits(*expected.iter().max().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_elementwise_overflow() {
+        let a = CompactVector::from_slice(&[0, usize::MAX]).unwrap();
+        let b = CompactVector::from_slice(&[1, 1]).unwrap();
+        let e = a.add_elementwise(&b);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("width must be in 1..=64, but got 65.".to_string())
+            Some("the sum at 1 must not overflow usize.".to_string())
         );
     }
 
     #[test]
-    fn test_with_capacity_oob_0() {
-        let e = CompactVector::with_capacity(0, 0);
+    fn test_add_elementwise_diff_len() {
+        let a = CompactVector::from_slice(&[0, 1]).unwrap();
+        let b = CompactVector::from_slice(&[1]).unwrap();
+        let e = a.add_elementwise(&b);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("width must be in 1..=64, but got 0.".to_string())
+            Some("other.len() must be equal to self.len()=2, but got 1.".to_string())
         );
     }
 
     #[test]
-    fn test_with_capacity_oob_65() {
-        let e = CompactVector::with_capacity(0, 65);
+    fn test_from_bit_vector() {
+        for &width in &[1, 7, 64] {
+            let vals = gen_random_ints(200, usize::MAX >> (64 - width), 71);
+            let mut bv = BitVector::new();
+            for &x in &vals {
+                bv.push_bits(x, width).unwrap();
+            }
+            let cv = CompactVector::from_bit_vector(bv, width).unwrap();
+            assert_eq!(cv.len(), vals.len());
+            assert_eq!(cv.width(), width);
+            for (i, &x) in vals.iter().enumerate() {
+                assert_eq!(cv.get_int(i), Some(x));
+            }
+            assert_eq!(cv, CompactVector::from_slice(&vals).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_from_bit_vector_not_multiple() {
+        let bv = BitVector::from_bit(false, 10);
+        let e = CompactVector::from_bit_vector(bv, 3);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("width must be in 1..=64, but got 65.".to_string())
+            Some("chunks.len() must be a multiple of width=3, but got 10.".to_string())
         );
     }
 
     #[test]
-    fn test_from_int_oob_0() {
-        let e = CompactVector::from_int(0, 0, 0);
+    fn test_from_bit_vector_0() {
+        let e = CompactVector::from_bit_vector(BitVector::new(), 0);
         assert_eq!(
             e.err().map(|x| x.to_string()),
             Some("width must be in 1..=64, but got 0.".to_string())
@@ -578,85 +4529,524 @@ mod tests {
     }
 
     #[test]
-    fn test_from_int_oob_65() {
-        let e = CompactVector::from_int(0, 0, 65);
+    fn test_hamming_neighbors_random() {
+        let vals = gen_random_ints(1000, 255, 73);
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        for &query in &[0, 0b1010_1010, 255, 256] {
+            for radius in 0..3 {
+                let expected: Vec<_> = (0..vals.len())
+                    .filter(|&i| ((vals[i] ^ query).count_ones() as usize) <= radius)
+                    .collect();
+                assert_eq!(cv.hamming_neighbors(query, radius), expected);
+            }
+        }
+        let exact: Vec<_> = (0..vals.len()).filter(|&i| vals[i] == 77).collect();
+        assert_eq!(cv.hamming_neighbors(77, 0), exact);
+    }
+
+    #[test]
+    fn test_serialize_into_slice() {
+        let cv = CompactVector::from_slice(&gen_random_ints(100, 1000, 79)).unwrap();
+        let mut expected = vec![];
+        cv.serialize_into(&mut expected).unwrap();
+        let size = cv.serialized_size();
+        assert_eq!(size, expected.len());
+
+        let mut buf = vec![0; size];
+        assert_eq!(cv.serialize_into_slice(&mut buf).unwrap(), size);
+        assert_eq!(buf, expected);
+
+        let mut buf = vec![0xff; size + 10];
+        assert_eq!(cv.serialize_into_slice(&mut buf).unwrap(), size);
+        assert_eq!(&buf[..size], &expected[..]);
+        assert!(buf[size..].iter().all(|&b| b == 0xff));
+    }
+
+    #[test]
+    fn test_serialize_into_slice_too_small() {
+        let cv = CompactVector::from_slice(&[1, 2, 3]).unwrap();
+        let size = cv.serialized_size();
+        let mut buf = vec![0; size - 1];
+        let e = cv.serialize_into_slice(&mut buf);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("width must be in 1..=64, but got 65.".to_string())
+            Some(format!(
+                "buf must have at least {size} bytes, but got {}.",
+                size - 1
+            ))
         );
     }
 
     #[test]
-    fn test_from_int_unfit() {
-        let e = CompactVector::from_int(4, 0, 2);
+    fn test_common_prefix_len_random() {
+        let mut rng = ChaChaRng::seed_from_u64(83);
+        for &width in &[1, 3, 13, 64] {
+            for _ in 0..20 {
+                let a = gen_random_ints(300, usize::MAX >> (64 - width), rng.gen());
+                let mut b = a.clone();
+                let p = rng.gen_range(0..=a.len());
+                b.truncate(rng.gen_range(p..=a.len()));
+                if p < b.len() {
+                    b[p] ^= 1;
+                }
+                let mut cva = CompactVector::new(width).unwrap();
+                cva.extend(a.iter().cloned()).unwrap();
+                let mut cvb = CompactVector::new(width).unwrap();
+                cvb.extend(b.iter().cloned()).unwrap();
+                let expected = p.min(b.len());
+                assert_eq!(cva.common_prefix_len(&cvb), expected);
+                assert_eq!(cvb.common_prefix_len(&cva), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_common_prefix_len_cases() {
+        let a = CompactVector::from_slice(&[4, 5, 6]).unwrap();
+        assert_eq!(a.common_prefix_len(&a.clone()), 3);
+
+        let b = CompactVector::from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(a.common_prefix_len(&b), 0);
+
+        let mut c = CompactVector::new(40).unwrap();
+        c.extend([4, 5, 7]).unwrap();
+        assert_eq!(a.common_prefix_len(&c), 2);
+        assert_eq!(c.common_prefix_len(&a), 2);
+
+        let e = CompactVector::new(3).unwrap();
+        assert_eq!(a.common_prefix_len(&e), 0);
+    }
+
+    #[test]
+    fn test_bits_per_element() {
+        let cv = CompactVector::from_int(7, 1000, 3).unwrap();
+        // 47 words of 3000 bits and 4 fields.
+        assert_eq!(cv.bits_per_element(), (47 + 4) as f64 * 64.0 / 1000.0);
+
+        let cv = CompactVector::with_capacity(100, 3).unwrap();
+        assert!(cv.bits_per_element().is_nan());
+        assert!(cv.bits_per_element_in_memory().is_nan());
+
+        let mut cv = CompactVector::with_capacity(1000, 3).unwrap();
+        cv.extend(std::iter::repeat(7).take(1000)).unwrap();
+        assert!(3.0 <= cv.bits_per_element_in_memory());
+    }
+
+    #[test]
+    fn test_factorize_random() {
+        for &max in &[5, 1 << 40] {
+            let vals = gen_random_ints(1000, max, 97);
+            let cv = CompactVector::from_slice(&vals).unwrap();
+            let (distinct, codes) = cv.factorize().unwrap();
+            assert_eq!(codes.len(), vals.len());
+            for (i, &x) in vals.iter().enumerate() {
+                assert_eq!(distinct.select(codes.get_int(i).unwrap()), Some(x));
+            }
+            let mut sorted = vals.clone();
+            sorted.sort_unstable();
+            sorted.dedup();
+            assert_eq!(distinct.iter(0).collect::<Vec<_>>(), sorted);
+        }
+    }
+
+    #[test]
+    fn test_factorize_max() {
+        let cv = CompactVector::from_slice(&[0, usize::MAX]).unwrap();
+        let e = cv.factorize();
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("val must fit in width=2 bits, but got 4.".to_string())
+            Some("integers must not contain usize::MAX.".to_string())
         );
+        let (distinct, codes) = CompactVector::new(2).unwrap().factorize().unwrap();
+        assert!(distinct.is_empty());
+        assert!(codes.is_empty());
     }
 
     #[test]
-    fn test_from_slice_uncastable() {
-        let e = CompactVector::from_slice(&[u128::MAX]);
+    fn test_compressed_histogram_random() {
+        for &max in &[30, 1 << 40] {
+            let vals = gen_random_ints(1000, max, 107);
+            let cv = CompactVector::from_slice(&vals).unwrap();
+            let (distinct, counts) = cv.compressed_histogram().unwrap();
+
+            let mut tally = HashMap::new();
+            vals.iter().for_each(|&x| *tally.entry(x).or_insert(0) += 1);
+            assert_eq!(distinct.len(), tally.len());
+            assert_eq!(counts.len(), tally.len());
+            for k in 0..distinct.len() {
+                let x = distinct.select(k).unwrap();
+                assert_eq!(counts.get_int(k), Some(tally[&x]));
+            }
+        }
+        let (distinct, counts) = CompactVector::new(3)
+            .unwrap()
+            .compressed_histogram()
+            .unwrap();
+        assert!(distinct.is_empty());
+        assert!(counts.is_empty());
+    }
+
+    #[test]
+    fn test_top_k_positions_random() {
+        let vals = gen_random_ints(500, 50, 109);
+        let cv = CompactVector::from_slice(&vals).unwrap();
+        let mut pairs: Vec<_> = vals.iter().enumerate().map(|(i, &x)| (x, i)).collect();
+        pairs.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for &k in &[0, 1, 10, 499, 500, 1000] {
+            let expected: Vec<_> = pairs.iter().take(k).map(|&(_, i)| i).collect();
+            assert_eq!(cv.top_k_positions(k), expected);
+        }
+        assert!(CompactVector::new(3).unwrap().top_k_positions(3).is_empty());
+    }
+
+    #[test]
+    fn test_rolling_hashes_random() {
+        let from_scratch = |xs: &[usize]| {
+            xs.iter().fold(0u128, |h, &x| {
+                let x =
+                    (x as u128 % ROLLING_HASH_MODULUS as u128 + 1) % ROLLING_HASH_MODULUS as u128;
+                (h * ROLLING_HASH_BASE as u128 + x) % ROLLING_HASH_MODULUS as u128
+            }) as u64
+        };
+        for &max in &[3, usize::MAX] {
+            let vals = gen_random_ints(300, max, 113);
+            let cv = CompactVector::from_slice(&vals).unwrap();
+            for &window in &[1, 2, 7, 300, 301] {
+                let expected: Vec<_> = vals.windows(window).map(from_scratch).collect();
+                assert_eq!(cv.rolling_hashes(window).unwrap(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolling_hashes_0() {
+        let cv = CompactVector::from_slice(&[1, 2]).unwrap();
+        let e = cv.rolling_hashes(0);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("vals must consist only of values castable into usize.".to_string())
+            Some("window must not be zero.".to_string())
         );
     }
 
     #[test]
-    fn test_set_int_oob() {
-        let mut cv = CompactVector::from_int(0, 1, 2).unwrap();
-        let e = cv.set_int(1, 1);
+    fn test_serialize_ef_random() {
+        let mut rng = ChaChaRng::seed_from_u64(127);
+        for &(width, max) in &[(1, 1), (8, 255), (32, 100), (64, usize::MAX), (64, 10)] {
+            let mut cv = CompactVector::new(width).unwrap();
+            for _ in 0..1000 {
+                cv.push_int(rng.gen_range(0..=max)).unwrap();
+            }
+            let mut bytes = vec![];
+            let size = cv.serialize_ef_into(&mut bytes).unwrap();
+            assert_eq!(size, bytes.len());
+            let other = CompactVector::deserialize_ef_from(&bytes[..]).unwrap();
+            assert_eq!(other, cv);
+        }
+    }
+
+    #[test]
+    fn test_serialize_ef_skewed() {
+        let mut rng = ChaChaRng::seed_from_u64(131);
+        let mut cv = CompactVector::new(40).unwrap();
+        for _ in 0..10000 {
+            // Geometric-like distribution mostly in a few bits.
+            let x = rng.gen_range(0..16) >> rng.gen_range(0..4);
+            cv.push_int(x).unwrap();
+        }
+        let mut plain = vec![];
+        cv.serialize_into(&mut plain).unwrap();
+        let mut ef = vec![];
+        cv.serialize_ef_into(&mut ef).unwrap();
+        assert!(ef.len() * 5 < plain.len());
+        assert_eq!(CompactVector::deserialize_ef_from(&ef[..]).unwrap(), cv);
+    }
+
+    #[test]
+    fn test_serialize_ef_empty() {
+        for cv in [CompactVector::default(), CompactVector::new(7).unwrap()] {
+            let mut bytes = vec![];
+            cv.serialize_ef_into(&mut bytes).unwrap();
+            assert_eq!(CompactVector::deserialize_ef_from(&bytes[..]).unwrap(), cv);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_ef_corrupt() {
+        let mut bytes = vec![];
+        64usize.serialize_into(&mut bytes).unwrap();
+        usize::MAX.serialize_into(&mut bytes).unwrap();
+        0usize.serialize_into(&mut bytes).unwrap();
+        BitVector::new().serialize_into(&mut bytes).unwrap();
+        BitVector::new().serialize_into(&mut bytes).unwrap();
+        let e = CompactVector::deserialize_ef_from(&bytes[..]);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("pos must be no greater than self.len()=1, but got 1.".to_string())
+            Some(format!(
+                "len*low_len must fit in usize, but got {}*64.",
+                usize::MAX
+            ))
+        );
+
+        let mut bytes = vec![];
+        64usize.serialize_into(&mut bytes).unwrap();
+        usize::MAX.serialize_into(&mut bytes).unwrap();
+        64usize.serialize_into(&mut bytes).unwrap();
+        BitVector::new().serialize_into(&mut bytes).unwrap();
+        BitVector::from_bits([true, true])
+            .serialize_into(&mut bytes)
+            .unwrap();
+        let e = CompactVector::deserialize_ef_from(&bytes[..]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "high bits must consist of {} unary codes.",
+                usize::MAX
+            ))
         );
     }
 
     #[test]
-    fn test_set_int_unfit() {
-        let mut cv = CompactVector::from_int(0, 1, 2).unwrap();
-        let e = cv.set_int(0, 4);
+    fn test_optimal_high_bits() {
+        let brute_force = |cv: &CompactVector| {
+            (0..=cv.width())
+                .min_by_key(|&h| {
+                    let l = cv.width() - h;
+                    let highs: u128 = cv
+                        .iter()
+                        .map(|x| x.checked_shr(l as u32).unwrap_or(0) as u128)
+                        .sum();
+                    (cv.len() * (l + 1)) as u128 + highs
+                })
+                .unwrap()
+        };
+
+        // Uniform
+        let mut cv = CompactVector::new(10).unwrap();
+        cv.extend(0..1024).unwrap();
+        assert_eq!(cv.optimal_high_bits(), 1);
+        assert_eq!(cv.optimal_high_bits(), brute_force(&cv));
+
+        // Clustered
+        let mut cv = CompactVector::new(10).unwrap();
+        cv.extend((0..1024).map(|i| i % 4)).unwrap();
+        assert_eq!(cv.optimal_high_bits(), 9);
+        assert_eq!(cv.optimal_high_bits(), brute_force(&cv));
+
+        let mut rng = ChaChaRng::seed_from_u64(137);
+        for &width in &[1, 5, 20, 64] {
+            for &max in &[1, 100, usize::MAX >> (64 - width)] {
+                let mut cv = CompactVector::new(width).unwrap();
+                for _ in 0..300 {
+                    cv.push_int(rng.gen_range(0..=max.min(usize::MAX >> (64 - width))))
+                        .unwrap();
+                }
+                assert_eq!(cv.optimal_high_bits(), brute_force(&cv));
+            }
+        }
+        assert_eq!(CompactVector::default().optimal_high_bits(), 0);
+    }
+
+    #[test]
+    fn test_pad_random() {
+        let mut rng = ChaChaRng::seed_from_u64(139);
+        for &width in &[1, 5, 33, 64] {
+            let max = usize::MAX >> (64 - width);
+            let mut cv = CompactVector::new(width).unwrap();
+            let mut expected = vec![];
+            for _ in 0..20 {
+                let count = rng.gen_range(0..50);
+                let val = rng.gen_range(0..=max);
+                if rng.gen_bool(0.5) {
+                    cv.pad_start(count, val).unwrap();
+                    expected.splice(0..0, std::iter::repeat(val).take(count));
+                } else {
+                    cv.pad_end(count, val).unwrap();
+                    expected.extend(std::iter::repeat(val).take(count));
+                }
+                assert_eq!(cv.len(), expected.len());
+                assert_eq!(cv.iter().collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pad_empty() {
+        let mut cv = CompactVector::new(3).unwrap();
+        cv.pad_start(2, 5).unwrap();
+        assert_eq!(cv.iter().collect::<Vec<_>>(), vec![5, 5]);
+
+        let mut cv = CompactVector::new(3).unwrap();
+        cv.pad_end(3, 1).unwrap();
+        assert_eq!(cv.iter().collect::<Vec<_>>(), vec![1, 1, 1]);
+
+        cv.pad_start(0, 7).unwrap();
+        assert_eq!(cv.len(), 3);
+    }
+
+    #[test]
+    fn test_pad_unfit() {
+        let mut cv = CompactVector::new(3).unwrap();
+        let e = cv.pad_start(1, 8);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("val must fit in self.width()=2 bits, but got 4.".to_string())
+            Some("val must fit in self.width()=3 bits, but got 8.".to_string())
+        );
+        let e = cv.pad_end(1, 8);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("val must fit in self.width()=3 bits, but got 8.".to_string())
         );
     }
 
     #[test]
-    fn test_push_int_unfit() {
-        let mut cv = CompactVector::new(2).unwrap();
-        let e = cv.push_int(4);
+    fn test_from_rows() {
+        let rows: Vec<Vec<usize>> = (0..5).map(|r| gen_random_ints(7, 1000, r)).collect();
+        let (cv, cols) = CompactVector::from_rows(&rows).unwrap();
+        assert_eq!(cols, 7);
+        assert_eq!(cv.len(), 35);
+        assert_eq!(
+            cv.width(),
+            utils::needed_bits(*rows.iter().flatten().max().unwrap())
+        );
+        for (i, row) in rows.iter().enumerate() {
+            for (j, &x) in row.iter().enumerate() {
+                assert_eq!(cv.get_int(i * cols + j), Some(x));
+            }
+        }
+
+        let (cv, cols) = CompactVector::from_rows(&[]).unwrap();
+        assert!(cv.is_empty());
+        assert_eq!(cols, 0);
+    }
+
+    #[test]
+    fn test_from_rows_ragged() {
+        let e = CompactVector::from_rows(&[vec![1, 2], vec![3, 4], vec![5]]);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("val must fit in self.width()=2 bits, but got 4.".to_string())
+            Some("rows must have the same length 2, but got 1 at row 2.".to_string())
         );
     }
 
     #[test]
-    fn test_extend_unfit() {
-        let mut cv = CompactVector::new(2).unwrap();
-        let e = cv.extend([4]);
+    fn test_stride_iter() {
+        let cv = CompactVector::from_slice(&gen_random_ints(100, 1000, 151)).unwrap();
+        for &start in &[0, 1, 50, 99, 100, 200] {
+            for &stride in &[1, 2, 7, 99, 100, usize::MAX] {
+                let mut expected = vec![];
+                let mut pos = start;
+                while pos < cv.len() {
+                    expected.push(cv.get_int(pos).unwrap());
+                    pos = match pos.checked_add(stride) {
+                        Some(pos) => pos,
+                        None => break,
+                    };
+                }
+                let it = cv.stride_iter(start, stride).unwrap();
+                assert_eq!(it.len(), expected.len());
+                assert_eq!(it.collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stride_iter_0() {
+        let cv = CompactVector::from_slice(&[1, 2]).unwrap();
+        let e = cv.stride_iter(0, 0);
         assert_eq!(
             e.err().map(|x| x.to_string()),
-            Some("val must fit in self.width()=2 bits, but got 4.".to_string())
+            Some("stride must not be zero.".to_string())
         );
     }
 
     #[test]
-    fn test_64b() {
-        let mut cv = CompactVector::new(64).unwrap();
-        cv.push_int(42).unwrap();
-        assert_eq!(cv.get_int(0), Some(42));
-        cv.set_int(0, 334).unwrap();
-        assert_eq!(cv.get_int(0), Some(334));
+    fn test_set_where_random() {
+        let mut rng = ChaChaRng::seed_from_u64(157);
+        for &width in &[1, 2, 3, 7, 16, 17, 64] {
+            let max = (usize::MAX >> (64 - width)).min(9);
+            let mut vals: Vec<usize> = (0..500).map(|_| rng.gen_range(0..=max)).collect();
+            let mut cv = CompactVector::new(width).unwrap();
+            cv.extend(vals.iter().cloned()).unwrap();
+
+            let threshold = max / 2;
+            let val = rng.gen_range(0..=max);
+            let count = cv.set_where(|x| x > threshold, val).unwrap();
+            let mut expected = 0;
+            for x in vals.iter_mut() {
+                if *x > threshold {
+                    if *x != val {
+                        expected += 1;
+                    }
+                    *x = val;
+                }
+            }
+            assert_eq!(count, expected);
+            assert_eq!(cv.iter().collect::<Vec<_>>(), vals);
+
+            for _ in 0..5 {
+                let old = rng.gen_range(0..=max);
+                let new = rng.gen_range(0..=max);
+                let count = cv.replace_value(old, new).unwrap();
+                let mut expected = 0;
+                for x in vals.iter_mut() {
+                    if *x == old {
+                        if *x != new {
+                            expected += 1;
+                        }
+                        *x = new;
+                    }
+                }
+                assert_eq!(count, expected);
+                assert_eq!(cv.iter().collect::<Vec<_>>(), vals);
+            }
+        }
     }
 
     #[test]
-    fn test_64b_from_int() {
-        let cv = CompactVector::from_int(42, 1, 64).unwrap();
-        assert_eq!(cv.get_int(0), Some(42));
+    fn test_replace_value_unfit() {
+        let mut cv = CompactVector::from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(cv.replace_value(4, 0).unwrap(), 0);
+        assert_eq!(cv.replace_value(2, 2).unwrap(), 0);
+        assert_eq!(cv.set_where(|x| x == 3, 3).unwrap(), 0);
+        assert_eq!(CompactVector::default().replace_value(0, 0).unwrap(), 0);
+        let e = cv.replace_value(1, 4);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("new must fit in self.width()=2 bits, but got 4.".to_string())
+        );
+        let e = cv.set_where(|_| true, 4);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("val must fit in self.width()=2 bits, but got 4.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_to_arrow_primitive() {
+        use std::convert::TryInto;
+
+        let mut rng = ChaChaRng::seed_from_u64(163);
+        for &width in &[1, 31, 32, 33, 64] {
+            let mut cv = CompactVector::new(width).unwrap();
+            for _ in 0..100 {
+                cv.push_int(rng.gen::<usize>() >> (64 - width)).unwrap();
+            }
+            let (buf, len) = cv.to_arrow_primitive();
+            assert_eq!(len, cv.len());
+            let vals: Vec<usize> = if width <= 32 {
+                assert_eq!(buf.len(), len * 4);
+                buf.chunks_exact(4)
+                    .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+                    .collect()
+            } else {
+                assert_eq!(buf.len(), len * 8);
+                buf.chunks_exact(8)
+                    .map(|b| u64::from_le_bytes(b.try_into().unwrap()) as usize)
+                    .collect()
+            };
+            assert_eq!(vals, cv.iter().collect::<Vec<_>>());
+        }
+        assert_eq!(CompactVector::default().to_arrow_primitive(), (vec![], 0));
     }
 
     #[test]
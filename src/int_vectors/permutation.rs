@@ -0,0 +1,289 @@
+//! Permutation supporting both forward and inverse lookups.
+#![cfg(target_pointer_width = "64")]
+
+use std::io::{Read, Write};
+
+use anyhow::{anyhow, Result};
+
+use crate::bit_vectors::{Access, BitVector, Rank, Rank9Sel};
+use crate::int_vectors::CompactVector;
+use crate::{utils, Serializable};
+
+/// Permutation supporting both forward and inverse lookups
+/// without storing the inverse mapping explicitly.
+///
+/// In addition to the forward mapping $`\pi`$ stored in [`CompactVector`],
+/// every `t`-th position on each cycle longer than `t` is marked
+/// and stores a back pointer to the previous marked position on the cycle.
+/// The inverse $`\pi^{-1}(i)`$ is computed by following the cycle from $`i`$
+/// and taking the back pointer at the first marked position, within at most $`2t`$ steps.
+///
+/// # Memory usage
+///
+/// $`n \lceil \lg n \rceil + n + o(n) + \frac{n}{t} \lceil \lg n \rceil`$ bits.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sucds::int_vectors::{CompactVector, Permutation};
+///
+/// let forward = CompactVector::from_slice(&[2, 0, 3, 4, 1])?;
+/// let perm = Permutation::new(forward, 2)?;
+///
+/// assert_eq!(perm.len(), 5);
+/// assert_eq!(perm.apply(0), Some(2));
+/// assert_eq!(perm.inverse(2), Some(0));
+/// assert_eq!(perm.inverse(1), Some(4));
+/// assert_eq!(perm.inverse(5), None);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # References
+///
+///  - J. I. Munro, R. Raman, V. Raman, and S. S. Rao, "Succinct representations of permutations
+///    and functions," Theoretical Computer Science, 2012.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Permutation {
+    forward: CompactVector,
+    marks: Rank9Sel,
+    backs: CompactVector,
+    t: usize,
+}
+
+impl Permutation {
+    /// Creates a new permutation from the forward mapping,
+    /// where the `i`-th integer of `forward` is the image of `i`.
+    ///
+    /// # Arguments
+    ///
+    /// - `forward`: Permutation of `0..forward.len()`.
+    /// - `t`: Parameter trading space for time, where [`Self::inverse()`] takes $`O(t)`$
+    ///   with $`\frac{n}{t} \lceil \lg n \rceil`$ bits of back pointers.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - `t == 0`, or
+    /// - `forward` is not a permutation of `0..forward.len()`.
+    pub fn new(forward: CompactVector, t: usize) -> Result<Self> {
+        if t == 0 {
+            return Err(anyhow!("t must be positive, but got 0."));
+        }
+        let n = forward.len();
+        let mut visited = BitVector::from_bit(false, n);
+        for (i, x) in forward.iter().enumerate() {
+            if n <= x || visited.access(x).unwrap() {
+                return Err(anyhow!(
+                    "forward must be a permutation of 0..{n}, but got {x} at {i}."
+                ));
+            }
+            visited.set_bit(x, true).unwrap();
+        }
+
+        // Marks every t-th position on each cycle longer than t.
+        let mut visited = BitVector::from_bit(false, n);
+        let mut marks = BitVector::from_bit(false, n);
+        let mut pairs = vec![];
+        for start in 0..n {
+            if visited.access(start).unwrap() {
+                continue;
+            }
+            let mut cycle = vec![];
+            let mut i = start;
+            while !visited.access(i).unwrap() {
+                visited.set_bit(i, true).unwrap();
+                cycle.push(i);
+                i = forward.get_int(i).unwrap();
+            }
+            if cycle.len() <= t {
+                continue;
+            }
+            let marked: Vec<_> = cycle.iter().cloned().step_by(t).collect();
+            for (k, &m) in marked.iter().enumerate() {
+                marks.set_bit(m, true).unwrap();
+                let prev = marked[(k + marked.len() - 1) % marked.len()];
+                pairs.push((m, prev));
+            }
+        }
+        pairs.sort_unstable();
+
+        let marks = Rank9Sel::new(marks);
+        let mut backs =
+            CompactVector::with_capacity(pairs.len(), utils::needed_bits(n.saturating_sub(1)))?;
+        for (_, prev) in pairs {
+            backs.push_int(prev)?;
+        }
+        Ok(Self {
+            forward,
+            marks,
+            backs,
+            t,
+        })
+    }
+
+    /// Returns the image of `i`, or [`None`] if `self.len() <= i`.
+    ///
+    /// # Arguments
+    ///
+    /// - `i`: Position.
+    ///
+    /// # Complexity
+    ///
+    /// Constant
+    #[inline(always)]
+    pub fn apply(&self, i: usize) -> Option<usize> {
+        self.forward.get_int(i)
+    }
+
+    /// Returns the preimage of `i`, or [`None`] if `self.len() <= i`.
+    ///
+    /// # Arguments
+    ///
+    /// - `i`: Position.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(t)`$
+    pub fn inverse(&self, i: usize) -> Option<usize> {
+        if self.len() <= i {
+            return None;
+        }
+        let mut j = i;
+        let mut jumped = false;
+        loop {
+            let next = self.forward.get_int(j).unwrap();
+            if next == i {
+                return Some(j);
+            }
+            if !jumped && self.marks.access(j).unwrap() {
+                j = self.backs.get_int(self.marks.rank1(j).unwrap()).unwrap();
+                jumped = true;
+            } else {
+                j = next;
+            }
+        }
+    }
+
+    /// Gets the number of elements.
+    pub const fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    /// Checks if the permutation is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the parameter trading space for time.
+    pub const fn t(&self) -> usize {
+        self.t
+    }
+}
+
+impl Serializable for Permutation {
+    fn serialize_into<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let mut mem = self.forward.serialize_into(&mut writer)?;
+        mem += self.marks.serialize_into(&mut writer)?;
+        mem += self.backs.serialize_into(&mut writer)?;
+        mem += self.t.serialize_into(&mut writer)?;
+        Ok(mem)
+    }
+
+    fn deserialize_from<R: Read>(mut reader: R) -> Result<Self> {
+        let forward = CompactVector::deserialize_from(&mut reader)?;
+        let marks = Rank9Sel::deserialize_from(&mut reader)?;
+        let backs = CompactVector::deserialize_from(&mut reader)?;
+        let t = usize::deserialize_from(&mut reader)?;
+        Ok(Self {
+            forward,
+            marks,
+            backs,
+            t,
+        })
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        self.forward.size_in_bytes()
+            + self.marks.size_in_bytes()
+            + self.backs.size_in_bytes()
+            + usize::size_of().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    fn gen_random_permutation(len: usize, seed: u64) -> Vec<usize> {
+        let mut rng = ChaChaRng::seed_from_u64(seed);
+        let mut perm: Vec<_> = (0..len).collect();
+        perm.shuffle(&mut rng);
+        perm
+    }
+
+    #[test]
+    fn test_inverse_random() {
+        for &len in &[1, 2, 100, 1000] {
+            let perm = gen_random_permutation(len, 101);
+            let forward = CompactVector::from_slice(&perm).unwrap();
+            for &t in &[1, 2, 8, 64, 2000] {
+                let p = Permutation::new(forward.clone(), t).unwrap();
+                for (i, &x) in perm.iter().enumerate() {
+                    assert_eq!(p.apply(i), Some(x));
+                    assert_eq!(p.apply(p.inverse(i).unwrap()), Some(i));
+                    assert_eq!(p.inverse(x), Some(i));
+                }
+                assert_eq!(p.apply(len), None);
+                assert_eq!(p.inverse(len), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_identity() {
+        let forward = CompactVector::from_slice(&[0, 1, 2, 3]).unwrap();
+        let p = Permutation::new(forward, 1).unwrap();
+        for i in 0..4 {
+            assert_eq!(p.inverse(i), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_not_permutation() {
+        let forward = CompactVector::from_slice(&[0, 2, 2]).unwrap();
+        let e = Permutation::new(forward, 2);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("forward must be a permutation of 0..3, but got 2 at 2.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_zero_t() {
+        let forward = CompactVector::from_slice(&[0]).unwrap();
+        let e = Permutation::new(forward, 0);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("t must be positive, but got 0.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut bytes = vec![];
+        let forward = CompactVector::from_slice(&gen_random_permutation(100, 103)).unwrap();
+        let p = Permutation::new(forward, 4).unwrap();
+        let size = p.serialize_into(&mut bytes).unwrap();
+        let other = Permutation::deserialize_from(&bytes[..]).unwrap();
+        assert_eq!(p, other);
+        assert_eq!(size, bytes.len());
+        assert_eq!(size, p.size_in_bytes());
+    }
+}
@@ -0,0 +1,201 @@
+//! Range minimum query index over a compact vector.
+#![cfg(target_pointer_width = "64")]
+
+use std::io::{Read, Write};
+
+use anyhow::Result;
+
+use crate::broadword;
+use crate::int_vectors::CompactVector;
+use crate::{utils, Serializable};
+
+/// Range minimum query index over [`CompactVector`] through the sparse table.
+///
+/// For each level $`k \geq 1`$, the table stores the position of the minimum in
+/// every range of length $`2^k`$ in [`CompactVector`] of $`\lceil \lg n \rceil`$ bits per entry.
+/// A query is answered by comparing the minimums of two overlapping ranges.
+///
+/// # Memory usage
+///
+/// $`n \lfloor \lg n \rfloor \lceil \lg n \rceil`$ bits for the table, in addition to the integers.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sucds::int_vectors::CompactVector;
+///
+/// let cv = CompactVector::from_slice(&[5, 2, 8, 2, 9, 1])?;
+/// let rmq = cv.build_rmq();
+///
+/// assert_eq!(rmq.range_min(0, 3), Some(1));
+/// assert_eq!(rmq.range_min(2, 5), Some(3));
+/// assert_eq!(rmq.range_min(0, 6), Some(5));
+/// assert_eq!(rmq.range_min(2, 2), None);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct RmqIndex {
+    vals: CompactVector,
+    levels: Vec<CompactVector>,
+}
+
+impl RmqIndex {
+    /// Creates a new index over `vals`.
+    ///
+    /// # Arguments
+    ///
+    /// - `vals`: Integers to be searched.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n \lg n)`$
+    pub fn new(vals: CompactVector) -> Self {
+        let n = vals.len();
+        let width = utils::needed_bits(n.saturating_sub(1));
+        let mut levels: Vec<CompactVector> = vec![];
+        let mut k = 1;
+        while 1 << k <= n {
+            let half = 1 << (k - 1);
+            // NOTE: Creating and pushing should be safe.
+            let mut level = CompactVector::with_capacity(n - (1 << k) + 1, width).unwrap();
+            for i in 0..=n - (1 << k) {
+                let (a, b) = levels.last().map_or_else(
+                    || (i, i + 1),
+                    |prev| (prev.get_int(i).unwrap(), prev.get_int(i + half).unwrap()),
+                );
+                level.push_int(Self::argmin(&vals, a, b)).unwrap();
+            }
+            levels.push(level);
+            k += 1;
+        }
+        Self { vals, levels }
+    }
+
+    /// Returns the position of the minimum integer in positions `l..r`
+    /// (the leftmost one if there are multiple), or [`None`] if `r <= l` or `self.len() < r`.
+    ///
+    /// # Arguments
+    ///
+    /// - `l`: Start position (inclusive).
+    /// - `r`: End position (exclusive).
+    ///
+    /// # Complexity
+    ///
+    /// Constant
+    pub fn range_min(&self, l: usize, r: usize) -> Option<usize> {
+        if r <= l || self.len() < r {
+            return None;
+        }
+        let k = broadword::msb(r - l).unwrap();
+        if k == 0 {
+            return Some(l);
+        }
+        let level = &self.levels[k - 1];
+        let a = level.get_int(l).unwrap();
+        let b = level.get_int(r - (1 << k)).unwrap();
+        Some(Self::argmin(&self.vals, a, b))
+    }
+
+    /// Returns the reference of the internal integers.
+    pub const fn vals(&self) -> &CompactVector {
+        &self.vals
+    }
+
+    /// Gets the number of integers.
+    pub const fn len(&self) -> usize {
+        self.vals.len()
+    }
+
+    /// Checks if the index has no integers.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `a` or `b` (`a < b`) in which the integer is no greater.
+    #[inline(always)]
+    fn argmin(vals: &CompactVector, a: usize, b: usize) -> usize {
+        if vals.get_int(b).unwrap() < vals.get_int(a).unwrap() {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+impl Serializable for RmqIndex {
+    fn serialize_into<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let mut mem = self.vals.serialize_into(&mut writer)?;
+        mem += self.levels.len().serialize_into(&mut writer)?;
+        for level in &self.levels {
+            mem += level.serialize_into(&mut writer)?;
+        }
+        Ok(mem)
+    }
+
+    fn deserialize_from<R: Read>(mut reader: R) -> Result<Self> {
+        let vals = CompactVector::deserialize_from(&mut reader)?;
+        let num_levels = usize::deserialize_from(&mut reader)?;
+        let mut levels = Vec::with_capacity(num_levels);
+        for _ in 0..num_levels {
+            levels.push(CompactVector::deserialize_from(&mut reader)?);
+        }
+        Ok(Self { vals, levels })
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        self.vals.size_in_bytes()
+            + usize::size_of().unwrap()
+            + self
+                .levels
+                .iter()
+                .map(|level| level.size_in_bytes())
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn test_range_min_random() {
+        let mut rng = ChaChaRng::seed_from_u64(89);
+        for &(len, max) in &[(1, 10), (2, 1), (100, 3), (257, 1000)] {
+            let vals: Vec<usize> = (0..len).map(|_| rng.gen_range(0..=max)).collect();
+            let rmq = CompactVector::from_slice(&vals).unwrap().build_rmq();
+            for l in 0..=len {
+                for r in l..=len {
+                    let expected = (l..r).min_by_key(|&i| vals[i]);
+                    assert_eq!(rmq.range_min(l, r), expected);
+                }
+            }
+            assert_eq!(rmq.range_min(0, len + 1), None);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let rmq = CompactVector::new(3).unwrap().build_rmq();
+        assert!(rmq.is_empty());
+        assert_eq!(rmq.range_min(0, 0), None);
+        assert_eq!(rmq.range_min(0, 1), None);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut bytes = vec![];
+        let rmq = CompactVector::from_slice(&[3, 1, 4, 1, 5])
+            .unwrap()
+            .build_rmq();
+        let size = rmq.serialize_into(&mut bytes).unwrap();
+        let other = RmqIndex::deserialize_from(&bytes[..]).unwrap();
+        assert_eq!(rmq, other);
+        assert_eq!(size, bytes.len());
+        assert_eq!(size, rmq.size_in_bytes());
+    }
+}
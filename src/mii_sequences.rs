@@ -31,7 +31,13 @@
 //!
 //! [`EliasFano`] is an efficient data structure for sparse sequences (i.e., $`n \ll u`$).
 //! In addition to the basic queires listed above, this provides several access queries such as binary search.
+//!
+//! ## Monotone minimal perfect hashing
+//!
+//! [`Mmphf`] maps each of sorted distinct keys to its rank on top of [`EliasFano`].
 pub mod elias_fano;
+pub mod mmphf;
 
 pub use elias_fano::EliasFano;
 pub use elias_fano::EliasFanoBuilder;
+pub use mmphf::Mmphf;
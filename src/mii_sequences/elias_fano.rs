@@ -117,6 +117,32 @@ impl EliasFano {
         Ok(b.build())
     }
 
+    /// Creates a new sequence from `len` sorted integers `vals` ending with `last`,
+    /// where the universe is the smallest possible one, i.e., `last + 1`.
+    /// The empty sequence is created if `last` is [`None`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    ///  - `last` is [`usize::MAX`], or
+    ///  - `vals` is not sorted or inconsistent with `len` and `last`.
+    pub(crate) fn from_sorted_tight<I>(vals: I, len: usize, last: Option<usize>) -> Result<Self>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let last = match last {
+            Some(last) => last,
+            None => return Ok(Self::default()),
+        };
+        let universe = last
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("integers must not contain usize::MAX."))?;
+        let mut efb = EliasFanoBuilder::new(universe, len)?;
+        efb.extend(vals)?;
+        Ok(efb.build())
+    }
+
     /// Builds an index to enable operations [`Self::rank()`],
     /// [`Self::predecessor()`], and [`Self::successor()`].
     #[must_use]
@@ -453,6 +479,78 @@ impl EliasFano {
         }
     }
 
+    /// Gets the successors of all the queries, i.e., the `i`-th answer is `self.successor(queries[i])`.
+    ///
+    /// Since the queries must be sorted, they are answered in a single forward sweep
+    /// sharing the cursor across queries.
+    /// If the cursor needs to move further than a threshold and [`Self::enable_rank()`] is set,
+    /// it jumps to the next answer with [`Self::rank()`].
+    /// Unlike [`Self::successor()`], the rank index is not required.
+    ///
+    /// # Arguments
+    ///
+    /// - `queries`: Successor queries sorted in increasing order.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `queries` is not sorted.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(n + m)`$ for $`m`$ queries, or $`O(m \lg \frac{u}{n})`$ with the rank index
+    /// if the queries are sparse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sucds::mii_sequences::EliasFanoBuilder;
+    ///
+    /// let mut efb = EliasFanoBuilder::new(8, 4)?;
+    /// efb.extend([1, 3, 3, 7])?;
+    /// let ef = efb.build();
+    ///
+    /// assert_eq!(
+    ///     ef.successors(&[0, 2, 3, 4, 8]),
+    ///     vec![Some(1), Some(3), Some(3), Some(7), None]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn successors(&self, queries: &[usize]) -> Vec<Option<usize>> {
+        assert!(
+            queries.windows(2).all(|w| w[0] <= w[1]),
+            "queries must be sorted."
+        );
+        if self.is_empty() {
+            return vec![None; queries.len()];
+        }
+        let mut answers = Vec::with_capacity(queries.len());
+        let mut it = self.iter(0);
+        let mut cur = it.next();
+        for &q in queries {
+            if self.universe() <= q {
+                answers.push(None);
+                continue;
+            }
+            let mut steps = 0;
+            while let Some(x) = cur {
+                if q <= x {
+                    break;
+                }
+                if steps == LINEAR_SCAN_THRESHOLD && self.has_rank() {
+                    it = self.iter(self.rank(q).unwrap());
+                    cur = it.next();
+                    break;
+                }
+                cur = it.next();
+                steps += 1;
+            }
+            answers.push(cur);
+        }
+        answers
+    }
+
     /// Creates an iterator of [`Iter`] to enumerate integers from the `k`-th one.
     ///
     /// # Arguments
@@ -477,7 +575,7 @@ impl EliasFano {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn iter(&self, k: usize) -> Iter {
+    pub fn iter(&self, k: usize) -> Iter<'_> {
         Iter::new(self, k)
     }
 
@@ -688,6 +786,9 @@ impl EliasFanoBuilder {
 mod tests {
     use super::*;
 
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
     #[test]
     fn test_from_bits_empty() {
         let e = EliasFano::from_bits([]);
@@ -719,6 +820,35 @@ mod tests {
         assert_eq!(size, ef.size_in_bytes());
     }
 
+    #[test]
+    fn test_successors() {
+        let mut rng = ChaChaRng::seed_from_u64(13);
+        let mut vals: Vec<usize> = (0..1000).map(|_| rng.gen_range(0..100000)).collect();
+        vals.sort_unstable();
+        let mut efb = EliasFanoBuilder::new(100000, vals.len()).unwrap();
+        efb.extend(vals.iter().cloned()).unwrap();
+        let ef = efb.build().enable_rank();
+        for num_queries in [0, 10, 1000, 10000] {
+            let mut queries: Vec<usize> =
+                (0..num_queries).map(|_| rng.gen_range(0..110000)).collect();
+            queries.sort_unstable();
+            let expected: Vec<_> = queries.iter().map(|&q| ef.successor(q)).collect();
+            assert_eq!(ef.successors(&queries), expected);
+            let without_rank = EliasFano {
+                high_bits: DArray::from_bits(ef.high_bits.bit_vector().iter()),
+                ..ef.clone()
+            };
+            assert_eq!(without_rank.successors(&queries), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "queries must be sorted.")]
+    fn test_successors_unsorted() {
+        let ef = EliasFano::from_bits([false, true, true]).unwrap();
+        ef.successors(&[0, 2, 1]);
+    }
+
     #[test]
     fn test_builder_new_zero_size() {
         let e = EliasFanoBuilder::new(3, 0);
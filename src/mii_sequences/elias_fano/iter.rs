@@ -25,6 +25,7 @@ impl<'a> Iter<'a> {
         let low_buf = 0;
         let low_mask = (1 << ef.low_len) - 1;
 
+        #[allow(clippy::manual_checked_ops)]
         let (chunks_in_word, chunks_avail) = if ef.low_len != 0 {
             (64 / ef.low_len, 0)
         } else {
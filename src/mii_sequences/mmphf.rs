@@ -0,0 +1,185 @@
+//! Monotone minimal perfect hash function over sorted distinct keys.
+#![cfg(target_pointer_width = "64")]
+
+use std::io::{Read, Write};
+
+use anyhow::{anyhow, Result};
+
+use crate::int_vectors::CompactVector;
+use crate::mii_sequences::EliasFano;
+use crate::Serializable;
+
+/// Monotone minimal perfect hash function mapping each of sorted distinct keys to its rank,
+/// i.e., its position in the sorted sequence.
+///
+/// This is backed by [`EliasFano`] over the keys, taking
+/// $`n \lceil \lg \frac{u}{n} \rceil + 2n + o(n)`$ bits for $`n`$ keys from $`[0, u)`$
+/// instead of storing the keys in plain form.
+/// Since the keys are implicitly stored, absent keys are detected.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sucds::int_vectors::CompactVector;
+///
+/// let keys = CompactVector::from_slice(&[3, 10, 64, 1000])?;
+/// let mmphf = keys.build_mmphf()?;
+///
+/// assert_eq!(mmphf.len(), 4);
+/// assert_eq!(mmphf.lookup(3), Some(0));
+/// assert_eq!(mmphf.lookup(64), Some(2));
+/// assert_eq!(mmphf.lookup(1000), Some(3));
+/// assert_eq!(mmphf.lookup(11), None);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Mmphf {
+    ef: EliasFano,
+}
+
+impl Mmphf {
+    /// Creates a new function over `keys`.
+    ///
+    /// # Arguments
+    ///
+    /// - `keys`: Strictly increasing keys.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - `keys` is not strictly increasing, or
+    /// - `keys` contains [`usize::MAX`].
+    pub fn new(keys: &CompactVector) -> Result<Self> {
+        let mut last = None;
+        for (i, x) in keys.iter().enumerate() {
+            if let Some(y) = last {
+                if x <= y {
+                    return Err(anyhow!(
+                        "keys must be strictly increasing, but got {y} at {} and {x} at {i}.",
+                        i - 1
+                    ));
+                }
+            }
+            last = Some(x);
+        }
+        let ef = EliasFano::from_sorted_tight(keys.iter(), keys.len(), last)?;
+        Ok(Self { ef })
+    }
+
+    /// Returns the rank of `key`, or [`None`] if `key` is not contained.
+    ///
+    /// # Arguments
+    ///
+    /// - `key`: Key to be searched.
+    ///
+    /// # Complexity
+    ///
+    /// $`O(\lg n)`$
+    pub fn lookup(&self, key: usize) -> Option<usize> {
+        if self.ef.universe() <= key {
+            return None;
+        }
+        self.ef.binsearch(key)
+    }
+
+    /// Gets the number of keys.
+    pub fn len(&self) -> usize {
+        self.ef.len()
+    }
+
+    /// Checks if the function has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Serializable for Mmphf {
+    fn serialize_into<W: Write>(&self, writer: W) -> Result<usize> {
+        self.ef.serialize_into(writer)
+    }
+
+    fn deserialize_from<R: Read>(reader: R) -> Result<Self> {
+        let ef = EliasFano::deserialize_from(reader)?;
+        Ok(Self { ef })
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        self.ef.size_in_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    fn gen_random_keys(len: usize, max: usize, seed: u64) -> Vec<usize> {
+        let mut rng = ChaChaRng::seed_from_u64(seed);
+        let mut keys: Vec<usize> = (0..len).map(|_| rng.gen_range(0..max)).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    #[test]
+    fn test_lookup_random() {
+        for &max in &[1000, 1 << 40] {
+            let keys = gen_random_keys(500, max, 53);
+            let mmphf = CompactVector::from_slice(&keys)
+                .unwrap()
+                .build_mmphf()
+                .unwrap();
+            assert_eq!(mmphf.len(), keys.len());
+            for (i, &x) in keys.iter().enumerate() {
+                assert_eq!(mmphf.lookup(x), Some(i));
+            }
+            assert_eq!(mmphf.lookup(keys.last().unwrap() + 1), None);
+        }
+    }
+
+    #[test]
+    fn test_lookup_absent() {
+        let keys = CompactVector::from_slice(&[0, 5, 6, 100]).unwrap();
+        let mmphf = Mmphf::new(&keys).unwrap();
+        for x in 0..=200 {
+            let expected = keys.iter().position(|k| k == x);
+            assert_eq!(mmphf.lookup(x), expected);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let mmphf = Mmphf::new(&CompactVector::new(3).unwrap()).unwrap();
+        assert!(mmphf.is_empty());
+        assert_eq!(mmphf.lookup(0), None);
+    }
+
+    #[test]
+    fn test_not_strictly_increasing() {
+        let keys = CompactVector::from_slice(&[1, 4, 4]).unwrap();
+        let e = Mmphf::new(&keys);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("keys must be strictly increasing, but got 4 at 1 and 4 at 2.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut bytes = vec![];
+        let mmphf = CompactVector::from_slice(&[1, 4, 9])
+            .unwrap()
+            .build_mmphf()
+            .unwrap();
+        let size = mmphf.serialize_into(&mut bytes).unwrap();
+        let other = Mmphf::deserialize_from(&bytes[..]).unwrap();
+        assert_eq!(mmphf, other);
+        assert_eq!(size, bytes.len());
+        assert_eq!(size, mmphf.size_in_bytes());
+    }
+}
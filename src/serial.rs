@@ -1,6 +1,7 @@
 //! Serializers.
 #![cfg(target_pointer_width = "64")]
 
+pub mod bundle;
 pub mod primitive;
 
 use std::io::{Read, Write};
@@ -0,0 +1,358 @@
+//! Container to serialize multiple data structures into a single stream.
+#![cfg(target_pointer_width = "64")]
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use anyhow::{anyhow, Result};
+
+use super::Serializable;
+
+/// Container to serialize multiple named members into a single stream,
+/// which can be loaded on demand through [`BundleReader`].
+///
+/// # Layout
+///
+/// A bundle consists of a directory followed by the concatenated members.
+/// The directory stores the number of members and, for each member in insertion order,
+/// its name (as `Vec<u8>`), offset, and length in bytes, all serialized with [`Serializable`].
+/// The offset of a member is relative to the end of the directory.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::io::Cursor;
+/// use sucds::bit_vectors::BitVector;
+/// use sucds::int_vectors::CompactVector;
+/// use sucds::serial::bundle::{Bundle, BundleReader};
+/// use sucds::Serializable;
+///
+/// let bv = BitVector::from_bits([true, false, true]);
+/// let cv = CompactVector::from_slice(&[5, 256, 0])?;
+///
+/// let mut bundle = Bundle::new();
+/// bundle.add("bv", &bv)?;
+/// bundle.add("cv", &cv)?;
+///
+/// let mut bytes = vec![];
+/// bundle.serialize_into(&mut bytes)?;
+///
+/// let mut reader = BundleReader::new(Cursor::new(bytes))?;
+/// assert_eq!(reader.load::<CompactVector>("cv")?, cv);
+/// assert_eq!(reader.load::<BitVector>("bv")?, bv);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    names: Vec<String>,
+    members: Vec<Vec<u8>>,
+}
+
+impl Bundle {
+    /// Creates a new empty bundle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a member of serialized `bytes` with `name`.
+    ///
+    /// # Arguments
+    ///
+    /// - `name`: Name of the member.
+    /// - `bytes`: Serialized bytes of the member.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `name` has already been added.
+    pub fn add_named(&mut self, name: &str, bytes: Vec<u8>) -> Result<()> {
+        if self.names.iter().any(|n| n == name) {
+            return Err(anyhow!("name must be unique, but got {name}."));
+        }
+        self.names.push(name.to_string());
+        self.members.push(bytes);
+        Ok(())
+    }
+
+    /// Serializes `member` and adds it with `name`.
+    ///
+    /// # Arguments
+    ///
+    /// - `name`: Name of the member.
+    /// - `member`: Data structure to be serialized.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - `name` has already been added, or
+    /// - `member` fails to be serialized.
+    pub fn add<S: Serializable>(&mut self, name: &str, member: &S) -> Result<()> {
+        let mut bytes = Vec::with_capacity(member.size_in_bytes());
+        member.serialize_into(&mut bytes)?;
+        self.add_named(name, bytes)
+    }
+
+    /// Gets the number of members.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Checks if the bundle is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Serializable for Bundle {
+    fn serialize_into<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let mut mem = self.len().serialize_into(&mut writer)?;
+        let mut offset = 0;
+        for (name, member) in self.names.iter().zip(&self.members) {
+            mem += name.as_bytes().to_vec().serialize_into(&mut writer)?;
+            mem += offset.serialize_into(&mut writer)?;
+            mem += member.len().serialize_into(&mut writer)?;
+            offset += member.len();
+        }
+        for member in &self.members {
+            writer.write_all(member)?;
+            mem += member.len();
+        }
+        Ok(mem)
+    }
+
+    fn deserialize_from<R: Read>(mut reader: R) -> Result<Self> {
+        let entries = read_directory(&mut reader)?;
+        let mut bundle = Self::new();
+        let mut expected = 0usize;
+        for (name, offset, len) in entries {
+            // NOTE: The members are read in order, so they must be contiguous.
+            if offset != expected {
+                return Err(anyhow!(
+                    "offset of {name} must be {expected}, but got {offset}."
+                ));
+            }
+            expected = offset
+                .checked_add(len)
+                .ok_or_else(|| anyhow!("offset+len of {name} must fit in usize."))?;
+            let mut bytes = vec![];
+            (&mut reader).take(len as u64).read_to_end(&mut bytes)?;
+            if bytes.len() != len {
+                return Err(anyhow!(
+                    "{name} must consist of {len} bytes, but got {}.",
+                    bytes.len()
+                ));
+            }
+            bundle.add_named(&name, bytes)?;
+        }
+        Ok(bundle)
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        let mut mem = usize::size_of().unwrap();
+        for (name, member) in self.names.iter().zip(&self.members) {
+            mem += usize::size_of().unwrap() * 3 + name.len() + member.len();
+        }
+        mem
+    }
+}
+
+/// Reader of a bundle serialized by [`Bundle`], loading a named member on demand.
+pub struct BundleReader<R> {
+    reader: R,
+    entries: Vec<(String, usize, usize)>,
+    base: u64,
+}
+
+impl<R> BundleReader<R>
+where
+    R: Read + Seek,
+{
+    /// Creates a new reader by loading the directory from the current position of `reader`.
+    ///
+    /// # Arguments
+    ///
+    /// - `reader`: Seekable reader positioned at the beginning of a bundle.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the directory fails to be loaded.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let entries = read_directory(&mut reader)?;
+        let base = reader.stream_position()?;
+        Ok(Self {
+            reader,
+            entries,
+            base,
+        })
+    }
+
+    /// Creates an iterator for enumerating the member names in insertion order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _, _)| name.as_str())
+    }
+
+    /// Loads the serialized bytes of the member `name`.
+    ///
+    /// # Arguments
+    ///
+    /// - `name`: Name of the member.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - `name` is not found, or
+    /// - reading from the underlying reader fails.
+    pub fn read_bytes(&mut self, name: &str) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        self.seek_member(name)?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Deserializes the member `name`.
+    ///
+    /// # Arguments
+    ///
+    /// - `name`: Name of the member.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if
+    ///
+    /// - `name` is not found, or
+    /// - the member fails to be deserialized.
+    pub fn load<S: Serializable>(&mut self, name: &str) -> Result<S> {
+        S::deserialize_from(self.seek_member(name)?)
+    }
+
+    fn seek_member(&mut self, name: &str) -> Result<std::io::Take<&mut R>> {
+        let &(_, offset, len) = self
+            .entries
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .ok_or_else(|| anyhow!("name must be in the bundle, but got {name}."))?;
+        let pos = self
+            .base
+            .checked_add(offset as u64)
+            .ok_or_else(|| anyhow!("offset of {name} must fit in u64, but got {offset}."))?;
+        self.reader.seek(SeekFrom::Start(pos))?;
+        Ok((&mut self.reader).take(len as u64))
+    }
+}
+
+fn read_directory<R: Read>(mut reader: R) -> Result<Vec<(String, usize, usize)>> {
+    let num_entries = usize::deserialize_from(&mut reader)?;
+    // NOTE: num_entries is not trusted, so the pre-allocation is capped.
+    let mut entries = Vec::with_capacity(num_entries.min(1 << 16));
+    for _ in 0..num_entries {
+        let name = String::from_utf8(Vec::<u8>::deserialize_from(&mut reader)?)?;
+        let offset = usize::deserialize_from(&mut reader)?;
+        let len = usize::deserialize_from(&mut reader)?;
+        entries.push((name, offset, len));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use crate::bit_vectors::BitVector;
+    use crate::int_vectors::CompactVector;
+    use crate::mii_sequences::EliasFano;
+
+    #[test]
+    fn test_reader() {
+        let bv = BitVector::from_bits([true, false, false, true, true]);
+        let cv1 = CompactVector::from_slice(&[7, 334, 1, 2]).unwrap();
+        let cv2 = CompactVector::from_slice(&[0, 1, 0]).unwrap();
+        let ef = EliasFano::from_bits([false, true, true, false, true]).unwrap();
+
+        let mut bundle = Bundle::new();
+        bundle.add("bv", &bv).unwrap();
+        bundle.add("cv1", &cv1).unwrap();
+        bundle.add("cv2", &cv2).unwrap();
+        bundle.add("ef", &ef).unwrap();
+
+        let mut bytes = vec![];
+        let size = bundle.serialize_into(&mut bytes).unwrap();
+        assert_eq!(size, bytes.len());
+        assert_eq!(size, bundle.size_in_bytes());
+
+        let mut reader = BundleReader::new(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(
+            reader.names().collect::<Vec<_>>(),
+            vec!["bv", "cv1", "cv2", "ef"]
+        );
+        assert_eq!(reader.load::<EliasFano>("ef").unwrap(), ef);
+        assert_eq!(reader.load::<CompactVector>("cv2").unwrap(), cv2);
+        assert_eq!(reader.load::<BitVector>("bv").unwrap(), bv);
+        assert_eq!(reader.load::<CompactVector>("cv1").unwrap(), cv1);
+
+        let other = Bundle::deserialize_from(&bytes[..]).unwrap();
+        assert_eq!(bundle, other);
+    }
+
+    #[test]
+    fn test_reader_missing() {
+        let mut bytes = vec![];
+        Bundle::new().serialize_into(&mut bytes).unwrap();
+        let mut reader = BundleReader::new(Cursor::new(bytes)).unwrap();
+        let e = reader.read_bytes("x");
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("name must be in the bundle, but got x.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_corrupt() {
+        let mut bundle = Bundle::new();
+        bundle.add_named("a", vec![1, 2]).unwrap();
+        bundle.add_named("b", vec![3]).unwrap();
+        let mut bytes = vec![];
+        bundle.serialize_into(&mut bytes).unwrap();
+
+        // The offset of "b" is at the end of its directory entry.
+        let pos = 8 + (8 + 1 + 8 + 8) + (8 + 1);
+        let mut broken = bytes.clone();
+        broken[pos..pos + 8].copy_from_slice(&0usize.to_le_bytes());
+        let e = Bundle::deserialize_from(&broken[..]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("offset of b must be 2, but got 0.".to_string())
+        );
+
+        let mut broken = bytes.clone();
+        broken[pos..pos + 8].copy_from_slice(&usize::MAX.to_le_bytes());
+        let mut reader = BundleReader::new(Cursor::new(broken)).unwrap();
+        let e = reader.read_bytes("b");
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some(format!(
+                "offset of b must fit in u64, but got {}.",
+                usize::MAX
+            ))
+        );
+
+        let e = Bundle::deserialize_from(&bytes[..bytes.len() - 1]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("b must consist of 1 bytes, but got 0.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_add_named_duplicate() {
+        let mut bundle = Bundle::new();
+        bundle.add_named("x", vec![1]).unwrap();
+        let e = bundle.add_named("x", vec![2]);
+        assert_eq!(
+            e.err().map(|x| x.to_string()),
+            Some("name must be unique, but got x.".to_string())
+        );
+    }
+}